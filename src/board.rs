@@ -0,0 +1,180 @@
+use crate::cell::Cell;
+use crate::piece::Piece;
+use crate::pos::Pos;
+
+pub const DEFAULT_WIDTH: i32 = 10;
+pub const DEFAULT_HEIGHT: i32 = 20;
+pub const DEFAULT_BUFFER: i32 = 20;
+// any piece has to fit, in every rotation
+pub const MIN_SIZE: i32 = 4;
+
+// only holds the locked cells, the falling piece lives in the game state.
+// rows 0 to height - 1 are the visible playfield, the hidden buffer rows sit
+// above it (from -1 up to -buffer) so pieces can spawn and stack past the skyline
+#[derive(Debug, Clone)]
+pub struct Board {
+    width: i32,
+    height: i32,
+    buffer: i32,
+    grid: Vec<Vec<Cell>>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(DEFAULT_WIDTH, DEFAULT_HEIGHT, DEFAULT_BUFFER)
+    }
+}
+
+impl Board {
+    pub fn new(width: i32, height: i32, buffer: i32) -> Self {
+        let width = width.max(MIN_SIZE);
+        let height = height.max(MIN_SIZE);
+        let buffer = buffer.max(0);
+        Self {
+            width,
+            height,
+            buffer,
+            grid: vec![vec![Cell::Empty; width as usize]; (buffer + height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    // visible rows only
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn buffer(&self) -> i32 {
+        self.buffer
+    }
+
+    // visible rows only, from the top
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.grid[self.buffer as usize..]
+            .iter()
+            .map(|row| row.as_slice())
+    }
+
+    pub fn cell(&self, pos: Pos) -> Cell {
+        if self.is_in_grid(pos) {
+            self.grid[self.row_index(pos.y)][pos.x as usize]
+        } else {
+            Cell::Empty
+        }
+    }
+
+    pub fn set_cell(&mut self, pos: Pos, cell: Cell) {
+        if self.is_in_grid(pos) {
+            let row = self.row_index(pos.y);
+            self.grid[row][pos.x as usize] = cell;
+        }
+    }
+
+    // buffer rows included
+    pub fn is_in_grid(&self, pos: Pos) -> bool {
+        (0..self.width()).contains(&pos.x) && (-self.buffer..self.height()).contains(&pos.y)
+    }
+
+    pub fn is_in_empty_pos(&self, pos: Pos) -> bool {
+        self.cell(pos) == Cell::Empty
+    }
+
+    // is the piece fully inside the grid and not overlapping anything
+    pub fn fits(&self, piece: &Piece) -> bool {
+        piece
+            .board_cells()
+            .into_iter()
+            .all(|pos| self.is_in_grid(pos) && self.is_in_empty_pos(pos))
+    }
+
+    pub fn place_piece(&mut self, piece: &Piece) {
+        for pos in piece.board_cells() {
+            self.set_cell(pos, Cell::Tetromino(piece.tetromino));
+        }
+    }
+
+    pub fn is_row_full(&self, row: i32) -> bool {
+        self.grid[self.row_index(row)]
+            .iter()
+            .all(|cell| *cell != Cell::Empty)
+    }
+
+    pub fn is_row_empty(&self, row: i32) -> bool {
+        self.grid[self.row_index(row)]
+            .iter()
+            .all(|cell| *cell == Cell::Empty)
+    }
+
+    // from the top
+    pub fn full_rows(&self) -> Vec<i32> {
+        (-self.buffer..self.height())
+            .filter(|y| self.is_row_full(*y))
+            .collect()
+    }
+
+    // would the board be empty once these rows are cleared
+    pub fn is_empty_without(&self, rows: &[i32]) -> bool {
+        (-self.buffer..self.height())
+            .filter(|y| !rows.contains(y))
+            .all(|y| self.is_row_empty(y))
+    }
+
+    // rows must be sorted from the top
+    pub fn clear_rows(&mut self, rows: &[i32]) {
+        for y in rows {
+            // move all rows above one row down
+            for y2 in (-self.buffer..*y).rev() {
+                self.copy_row_down(y2);
+            }
+
+            // clear top row
+            self.clear_row(-self.buffer);
+        }
+    }
+
+    // number of rows with garbage left in them
+    pub fn garbage_rows(&self) -> i32 {
+        self.garbage_rows_without(&[])
+    }
+
+    // same, once these rows are cleared
+    pub fn garbage_rows_without(&self, rows: &[i32]) -> i32 {
+        (-self.buffer..self.height())
+            .filter(|y| !rows.contains(y))
+            .filter(|y| self.grid[self.row_index(*y)].contains(&Cell::Garbage))
+            .count() as i32
+    }
+
+    // a row of garbage at the bottom with a hole at `hole`, everything else goes up a row.
+    // returns false if that pushed blocks off the top of the buffer
+    pub fn insert_garbage_row(&mut self, hole: i32) -> bool {
+        let mut row = vec![Cell::Garbage; self.width as usize];
+        if let Some(cell) = row.get_mut(hole as usize) {
+            *cell = Cell::Empty;
+        }
+        let top = self.grid.remove(0);
+        self.grid.push(row);
+        top.iter().all(|cell| *cell == Cell::Empty)
+    }
+
+    //--------------------------------------------------------------------------------
+    // helpers
+    //--------------------------------------------------------------------------------
+
+    fn row_index(&self, row: i32) -> usize {
+        (row + self.buffer) as usize
+    }
+
+    fn copy_row_down(&mut self, row: i32) {
+        let (from, to) = (self.row_index(row), self.row_index(row + 1));
+        self.grid[to] = self.grid[from].clone();
+    }
+
+    fn clear_row(&mut self, row: i32) {
+        let row = self.row_index(row);
+        self.grid[row].fill(Cell::Empty);
+    }
+}
@@ -4,5 +4,6 @@ use crate::tetromino::Tetromino;
 pub enum Cell {
     Empty,
     Tetromino(Tetromino),
-    Shadow,
+    // pushed up from the bottom rather than placed by a piece
+    Garbage,
 }
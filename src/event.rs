@@ -0,0 +1,36 @@
+use crate::gamestate::EndReason;
+use crate::piece::Piece;
+use crate::scoring::LineClear;
+use crate::spin::Spin;
+use crate::tetromino::Tetromino;
+
+// what happened during an update, drained by the frontend with `GameState::take_events`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    PieceSpawned(Tetromino),
+    // kick is the index of the SRS kick that was used, 0 means no kick
+    PieceRotated {
+        piece: Piece,
+        kick: usize,
+    },
+    HardDropped {
+        piece: Piece,
+        rows: i32,
+    },
+    PieceLocked(Piece),
+    PieceHeld(Tetromino),
+    // sent when the locked piece was spun into place, whether it cleared lines or not
+    Spin {
+        tetromino: Tetromino,
+        spin: Spin,
+        lines: i32,
+    },
+    LinesCleared {
+        clear: LineClear,
+        points: i32,
+    },
+    // the line clear left the board empty
+    PerfectClear,
+    LevelUp(i32),
+    GameOver(EndReason),
+}
@@ -1,470 +1,1481 @@
-use std::cmp::{max, min};
+use std::mem;
+use std::time::Duration;
 
-use ruscii::{drawing::Pencil, keyboard::Key, spatial::Vec2, terminal::Color};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::cell::Cell;
+use crate::board::Board;
+use crate::event::Event;
+use crate::handling::Handling;
+use crate::input::Input;
+use crate::kicks::kicks;
 use crate::piece::Piece;
+use crate::pos::Pos;
+use crate::randomizer::Randomizer;
+use crate::replay::{Replay, ReplayInput};
+use crate::rules::{Goal, Gravity, LevelGoal, LockReset, Rules, GRAVITY_20G};
+use crate::scoring::LineClear;
+use crate::spin::{detect_spin, Spin};
+use crate::stats::Stats;
 use crate::tetromino::Tetromino;
 
-const GRID_WIDTH: i32 = 11; // each unit == 2 dots
-const GRID_HEIGHT: i32 = 20;
+// the engine always runs at 60 frames per second, whatever the display does
+pub const FRAME_RATE: u32 = 60;
+pub const FRAME: Duration = Duration::from_nanos(1_000_000_000 / FRAME_RATE as u64);
+// don't try to catch up on more than this at once (e.g. after the process was suspended)
+const MAX_UPDATE_TIME: Duration = Duration::from_millis(250);
 
-const GAMEOVER_WIDTH: i32 = 11; // real size
-const GAMEOVER_HEIGHT: i32 = 3;
-
-#[derive(PartialEq)]
-enum RunningState {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunningState {
     Running,
+    // nothing moves, inputs are ignored until resume
+    Paused,
     GameOver,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndReason {
+    // the new piece overlaps the stack when it spawns
+    BlockOut,
+    // a piece locked entirely above the visible playfield
+    LockOut,
+    // garbage coming in from the bottom pushed blocks off the top
+    TopOut,
+    // the goal set by the rules was reached
+    Completed,
+}
+
+// what happens between two pieces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    // a piece is in play
+    Falling,
+    // full rows are still on the board, waiting to be removed
+    LineClear,
+    // entry delay (ARE): waiting for the next piece to come in
+    Entry,
+}
+
 pub struct GameState {
-    dimension: Vec2,
-    step: usize,
-    grid: [[Cell; GRID_WIDTH as usize]; GRID_HEIGHT as usize],
-    grid_pos: Vec2,
+    seed: u64,
+    rules: Rules,
+    // fixed frames played so far, and real time not yet turned into frames
+    frame: u64,
+    time_acc: Duration,
+    // fraction of a cell the current piece has fallen
+    gravity_acc: f64,
+    board: Board,
     running: RunningState,
-    pub prev_key: Option<Key>,
+    end_reason: Option<EndReason>,
+    phase: Phase,
+    phase_timer: Duration,
+    clearing_rows: Vec<i32>,
     // current piece being dropped
     current_piece: Option<Piece>,
-    drop_current_piece: bool,
+    // quarter turns and kick used by the last successful action, if that action was a rotation
+    last_rotation: Option<(i32, usize)>,
+    soft_drop: bool,
+    // auto shift: held directions (last one wins) and how long the active one has been held
+    handling: Handling,
+    held_directions: Vec<i32>,
+    das_timer: Duration,
+    arr_timer: Duration,
+    // lock delay: time spent on the ground, resets used, and lowest row reached so far
+    lock_timer: Duration,
+    lock_resets: u32,
+    lowest_row: i32,
+    // hold slot, only one swap per piece until it locks
+    hold_piece: Option<Tetromino>,
+    can_hold: bool,
     // next pieces
-    nb_next_pieces: i32,
+    randomizer: Box<dyn Randomizer>,
+    nb_next_pieces: usize,
     next_pieces: Vec<Tetromino>,
+    // garbage rows still to come, and where the hole of the last one was
+    garbage_rng: StdRng,
+    garbage_queue: i32,
+    garbage_hole: Option<i32>,
     // score
     lines_cleared: i32,
     score: i32,
+    // the part of the score from line clears and spins, drops don't count towards the level
+    clear_points: i32,
+    // lines awarded towards the variable level goal
+    goal_lines: i32,
     level: i32,
-    // gameover
-    gameover_pos: Vec2,
-    gameover_speed: Vec2,
+    // score when the goal was reached, and the frame the player went on past it (if they did)
+    completed_score: Option<i32>,
+    continued_at: Option<u64>,
+    // -1 until a piece clears lines, then counts the pieces in a row that did
+    combo: i32,
+    back_to_back: bool,
+    stats: Stats,
+    // events since the last call to take_events
+    events: Vec<Event>,
+    // every input that had a chance to do something, for replays
+    inputs: Vec<ReplayInput>,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
 }
 
 impl GameState {
-    pub fn new(dim: Vec2) -> Self {
+    pub fn new(seed: u64) -> Self {
+        Self::with_rules(seed, Rules::default())
+    }
+
+    pub fn with_rules(seed: u64, rules: Rules) -> Self {
         Self {
-            dimension: dim,
-            step: 0,
-            grid: [[Cell::Empty; GRID_WIDTH as usize]; GRID_HEIGHT as usize],
-            grid_pos: Vec2::xy((dim.x - GRID_WIDTH * 2) / 2, (dim.y - GRID_HEIGHT) / 2),
+            seed,
+            randomizer: rules.randomizer.build(seed),
+            board: Board::new(rules.board_width, rules.board_height, rules.buffer_rows),
+            garbage_queue: rules.garbage_rows,
+            rules,
+            frame: 0,
+            time_acc: Duration::ZERO,
+            gravity_acc: 0.0,
             running: RunningState::Running,
-            prev_key: None,
+            end_reason: None,
+            phase: Phase::Falling,
+            phase_timer: Duration::ZERO,
+            clearing_rows: vec![],
             current_piece: None,
-            drop_current_piece: false,
+            last_rotation: None,
+            soft_drop: false,
+            handling: Handling::default(),
+            held_directions: vec![],
+            das_timer: Duration::ZERO,
+            arr_timer: Duration::ZERO,
+            lock_timer: Duration::ZERO,
+            lock_resets: 0,
+            lowest_row: 0,
+            hold_piece: None,
+            can_hold: true,
             nb_next_pieces: 3,
             next_pieces: vec![],
+            // not the same sequence as the pieces
+            garbage_rng: StdRng::seed_from_u64(!seed),
+            garbage_hole: None,
             lines_cleared: 0,
             score: 0,
-            level: 1, // goes from 1 ro 10
-            gameover_pos: Vec2::zero(),
-            gameover_speed: Vec2::zero(),
+            clear_points: 0,
+            goal_lines: 0,
+            level: 1, // goes from 1 to rules.max_level
+            completed_score: None,
+            continued_at: None,
+            combo: -1,
+            back_to_back: false,
+            stats: Stats::default(),
+            events: vec![],
+            inputs: vec![],
         }
     }
 
     pub fn init(&mut self) {
         for _ in 0..self.nb_next_pieces {
-            self.next_pieces.push(Tetromino::random());
+            self.next_pieces.push(self.randomizer.next_piece());
         }
+        self.refill_garbage();
 
-        // self.init_with_all_pieces();
-        self.pick_current_piece();
+        self.spawn_next_piece();
     }
 
-    pub fn set_step(&mut self, step: usize) {
-        self.step = step;
+    //--------------------------------------------------------------------------------
+    // accessors
+    //--------------------------------------------------------------------------------
+
+    pub fn board(&self) -> &Board {
+        &self.board
     }
 
-    pub fn handle_keys_down(&mut self, keys_down: Vec<Key>) {
-        if keys_down.is_empty() {
-            self.drop_current_piece = false;
-            self.prev_key = None;
-        } else {
-            for key_down in keys_down {
-                self.handle_key_down(key_down);
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn handling(&self) -> &Handling {
+        &self.handling
+    }
+
+    pub fn set_handling(&mut self, handling: Handling) {
+        self.handling = handling;
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    // game time, only counts the frames actually played
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.frame * 1_000_000_000 / FRAME_RATE as u64)
+    }
+
+    pub fn running(&self) -> RunningState {
+        self.running
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.running == RunningState::GameOver
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.running == RunningState::Paused
+    }
+
+    pub fn end_reason(&self) -> Option<EndReason> {
+        self.end_reason
+    }
+
+    // None unless the goal was reached, even if the game went on after that
+    pub fn completed_score(&self) -> Option<i32> {
+        self.completed_score
+    }
+
+    // until the time goal, if there is one
+    pub fn remaining_time(&self) -> Option<Duration> {
+        match self.rules.goal {
+            Some(Goal::Time(time)) => Some(time.saturating_sub(self.elapsed())),
+            _ => None,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    // how far along the current delay we are, from 0 to 1
+    pub fn phase_progress(&self) -> f64 {
+        let delay = match self.phase {
+            Phase::Falling => return 0.0,
+            Phase::LineClear => self.rules.line_clear_delay,
+            Phase::Entry => self.rules.entry_delay,
+        };
+        (self.phase_timer.as_secs_f64() / delay.as_secs_f64()).min(1.0)
+    }
+
+    // the full rows waiting to be removed, during the line clear delay
+    pub fn clearing_rows(&self) -> &[i32] {
+        &self.clearing_rows
+    }
+
+    pub fn current_piece(&self) -> Option<Piece> {
+        self.current_piece
+    }
+
+    pub fn last_rotation(&self) -> Option<(i32, usize)> {
+        self.last_rotation
+    }
+
+    // where the current piece would land if dropped straight down
+    pub fn ghost_piece(&self) -> Option<Piece> {
+        let mut ghost = self.current_piece?;
+        loop {
+            let mut below = ghost;
+            below.pos.y += 1;
+            if !self.board.fits(&below) {
+                return Some(ghost);
             }
+            ghost = below;
+        }
+    }
+
+    pub fn hold_piece(&self) -> Option<Tetromino> {
+        self.hold_piece
+    }
+
+    pub fn can_hold(&self) -> bool {
+        self.rules.hold_enabled && self.can_hold
+    }
+
+    pub fn next_pieces(&self) -> &[Tetromino] {
+        &self.next_pieces
+    }
+
+    pub fn lines_cleared(&self) -> i32 {
+        self.lines_cleared
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+
+    pub fn combo(&self) -> i32 {
+        self.combo
+    }
+
+    pub fn back_to_back(&self) -> bool {
+        self.back_to_back
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    // rows of garbage on the board and still to come
+    pub fn garbage_left(&self) -> i32 {
+        self.garbage_queue + self.board.garbage_rows()
+    }
+
+    pub fn take_events(&mut self) -> Vec<Event> {
+        mem::take(&mut self.events)
+    }
+
+    // everything needed to play this game again, so far
+    pub fn replay(&self) -> Replay {
+        Replay {
+            seed: self.seed,
+            rules: self.rules.clone(),
+            handling: self.handling,
+            inputs: self.inputs.clone(),
+            continued_at: self.continued_at,
         }
     }
 
-    fn handle_key_down(&mut self, key_down: Key) {
-        if Some(key_down) == self.prev_key {
-            match key_down {
-                // don't repeat these
-                Key::Up | Key::Space | Key::Enter => {}
-                // everything else, we just slow down the repeat
-                _ => self.prev_key = None,
+    // level goals (marathon) can be played past, the game goes on without a goal
+    pub fn can_keep_going(&self) -> bool {
+        self.end_reason == Some(EndReason::Completed)
+            && self.continued_at.is_none()
+            && matches!(self.rules.goal, Some(Goal::Level(_)))
+    }
+
+    pub fn keep_going(&mut self) -> bool {
+        if !self.can_keep_going() {
+            return false;
+        }
+        self.running = RunningState::Running;
+        self.end_reason = None;
+        self.continued_at = Some(self.frame);
+        // the rows were cleared when the goal was reached, on to the next piece
+        self.enter_phase(Phase::Entry);
+        true
+    }
+
+    //--------------------------------------------------------------------------------
+    // inputs
+    //--------------------------------------------------------------------------------
+
+    // returns whether the input had any effect
+    pub fn press(&mut self, input: Input) -> bool {
+        if self.running != RunningState::Running {
+            return false;
+        }
+        self.record_input(input, true);
+
+        match input {
+            Input::MoveLeft => self.start_shift(-1),
+            Input::MoveRight => self.start_shift(1),
+            Input::RotateCw => self.rotate_current_piece(1).is_some(),
+            Input::RotateCcw => self.rotate_current_piece(-1).is_some(),
+            Input::Rotate180 => self.rotate_current_piece(2).is_some(),
+            Input::SoftDrop => {
+                self.soft_drop = true;
+                true
             }
+            Input::HardDrop => self.hard_drop_current_piece(),
+            Input::Hold => self.hold_current_piece(),
+        }
+    }
+
+    pub fn release(&mut self, input: Input) {
+        if self.running != RunningState::Running {
             return;
         }
+        self.record_input(input, false);
 
-        self.prev_key = None;
-        match key_down {
-            // Key::A => self.move_gpos(Vec2::xy(-2, 0)),
-            // Key::D => self.move_gpos(Vec2::xy(2, 0)),
-            // Key::W => self.move_gpos(Vec2::xy(0, -1)),
-            // Key::S => self.move_gpos(Vec2::xy(0, 1)),
-            Key::Up | Key::Space => _ = self.rotate_current_piece(),
-            Key::Down | Key::Enter => _ = self.drop_current_piece(),
-            Key::Left => _ = self.move_current_piece(Vec2::xy(-1, 0)),
-            Key::Right => _ = self.move_current_piece(Vec2::xy(1, 0)),
+        match input {
+            Input::MoveLeft => self.stop_shift(-1),
+            Input::MoveRight => self.stop_shift(1),
+            Input::SoftDrop => self.soft_drop = false,
             _ => (),
         }
-        self.prev_key = Some(key_down);
     }
 
-    pub fn update(&mut self) {
-        if self.running == RunningState::GameOver {
-            if (self.step as i32) % 4 == 0 {
-                self.gameover_pos += self.gameover_speed;
-                if self.gameover_pos.x + GAMEOVER_WIDTH == GRID_WIDTH * 2
-                    || self.gameover_pos.x == 0
-                {
-                    self.gameover_speed.x = -self.gameover_speed.x
-                }
-                if self.gameover_pos.y + GAMEOVER_HEIGHT == GRID_HEIGHT || self.gameover_pos.y == 0
-                {
-                    self.gameover_speed.y = -self.gameover_speed.y
-                }
-            }
+    // keys are likely to be released while paused, so nothing stays held across a pause
+    // (released like any other input, so that replays don't need to know about pauses)
+    pub fn pause(&mut self) {
+        if self.running != RunningState::Running {
             return;
         }
+        for dx in self.held_directions.clone() {
+            self.release(if dx < 0 {
+                Input::MoveLeft
+            } else {
+                Input::MoveRight
+            });
+        }
+        if self.soft_drop {
+            self.release(Input::SoftDrop);
+        }
+        self.running = RunningState::Paused;
+    }
 
-        // moving current piece
-        if self.current_piece.is_some() {
-            // remove shadow
-            for y in 0..GRID_HEIGHT {
-                for x in 0..GRID_WIDTH {
-                    if self.grid[y as usize][x as usize] == Cell::Shadow {
-                        self.grid[y as usize][x as usize] = Cell::Empty;
-                    }
-                }
-            }
+    pub fn resume(&mut self) {
+        if self.running == RunningState::Paused {
+            self.running = RunningState::Running;
+        }
+    }
 
-            let mut draw_current = true;
-            let step_delay = if self.drop_current_piece {
-                1
-            } else {
-                11 - self.level
-            };
-            // note: != 0 so we don't automatically go down
-            if (self.step as i32) % step_delay == 0 && self.step != 0 {
-                draw_current = self.move_current_piece(Vec2::xy(0, 1));
+    fn record_input(&mut self, input: Input, pressed: bool) {
+        self.inputs.push(ReplayInput {
+            frame: self.frame,
+            input,
+            pressed,
+        });
+    }
+
+    fn start_shift(&mut self, dx: i32) -> bool {
+        self.held_directions.retain(|held| *held != dx);
+        self.held_directions.push(dx);
+        self.das_timer = Duration::ZERO;
+        self.arr_timer = Duration::ZERO;
+        self.move_current_piece(Pos::xy(dx, 0))
+    }
+
+    fn stop_shift(&mut self, dx: i32) {
+        let was_active = self.held_directions.last() == Some(&dx);
+        self.held_directions.retain(|held| *held != dx);
+        if was_active {
+            // falling back to the other direction, which has to charge again
+            self.das_timer = Duration::ZERO;
+            self.arr_timer = Duration::ZERO;
+        }
+    }
+
+    // keeps charging even without a piece, so a new piece can shift right away
+    fn update_auto_shift(&mut self, dt: Duration) {
+        let Some(&dx) = self.held_directions.last() else {
+            return;
+        };
+
+        let was_charged = self.das_timer >= self.handling.das;
+        self.das_timer += dt;
+        if self.das_timer < self.handling.das || self.current_piece.is_none() {
+            return;
+        }
+
+        if self.handling.arr.is_zero() {
+            while self.move_current_piece(Pos::xy(dx, 0)) {}
+            return;
+        }
+
+        // the first repeat happens as soon as das is charged
+        if !was_charged {
+            self.arr_timer = self.handling.arr;
+        } else {
+            self.arr_timer += dt;
+        }
+        while self.arr_timer >= self.handling.arr {
+            self.arr_timer -= self.handling.arr;
+            if !self.move_current_piece(Pos::xy(dx, 0)) {
+                self.arr_timer = Duration::ZERO;
+                break;
             }
+        }
+    }
 
-            if draw_current {
-                // piece was able to go down
-                // let's remove it first so we can draw it properly in its new pos
-                let current_piece = self.current_piece.unwrap();
-                self.remove_piece(&current_piece);
+    //--------------------------------------------------------------------------------
+    // garbage
+    //--------------------------------------------------------------------------------
 
-                // draw shadow
-                let mut shadow_piece = current_piece.clone();
+    // push rows of garbage (one hole each) in from the bottom, right away
+    pub fn add_garbage(&mut self, rows: i32) {
+        for _ in 0..rows {
+            if self.running == RunningState::GameOver {
+                return;
+            }
 
-                while self.is_piece_in_grid(&shadow_piece)
-                    && self.is_piece_in_empty_pos(&shadow_piece)
-                {
-                    shadow_piece.pos.y = shadow_piece.pos.y + 1;
+            let width = self.board.width();
+            let messiness = self.rules.garbage_messiness.clamp(0.0, 1.0);
+            let hole = match self.garbage_hole {
+                // moving means going somewhere else
+                Some(hole) if self.garbage_rng.gen_bool(messiness) => {
+                    (hole + self.garbage_rng.gen_range(1..width)) % width
                 }
-                shadow_piece.pos.y = shadow_piece.pos.y - 1;
-                self.place_piece(&shadow_piece, true);
+                Some(hole) => hole,
+                None => self.garbage_rng.gen_range(0..width),
+            };
+            self.garbage_hole = Some(hole);
 
-                self.place_piece(&current_piece, false);
-            } else {
-                // piece reached the bottom
-                let current_piece = self.current_piece.unwrap();
-                self.place_piece(&current_piece, false);
-                self.score += 4;
-
-                // check if we have a full rows
-                for y in 0..GRID_HEIGHT {
-                    if self.is_row_full(y) {
-                        // move all rows above one row down
-                        self.score += GRID_WIDTH;
-                        self.lines_cleared += 1;
-                        for y2 in (0..y).rev() {
-                            self.copy_row_down(y2);
-                        }
-
-                        // clear top row
-                        self.clear_row(0);
-                    }
+            if !self.board.insert_garbage_row(hole) {
+                self.gameover(EndReason::TopOut);
+                return;
+            }
+            for row in &mut self.clearing_rows {
+                *row -= 1;
+            }
+            // the falling piece only moves if it's in the way, and tops out if it can't go up
+            if let Some(mut piece) = self.current_piece {
+                while !self.board.fits(&piece) && top_row(&piece) > -self.board.buffer() {
+                    piece.pos.y -= 1;
+                    self.lowest_row -= 1;
                 }
-
-                // pick a new piece
-                let new_piece = self.pick_current_piece();
-                if self.is_piece_in_empty_pos(&new_piece) {
-                    self.place_piece(&self.current_piece.unwrap(), true);
-                } else {
-                    self.gameover();
+                if !self.board.fits(&piece) {
+                    self.gameover(EndReason::TopOut);
+                    return;
                 }
+                self.current_piece = Some(piece);
             }
         }
-
-        self.level = min(10, max(1, 1 + self.score / 100));
     }
 
-    fn gameover(&mut self) {
-        self.running = RunningState::GameOver;
-        self.gameover_pos = Vec2::xy((GRID_WIDTH * 2 - GAMEOVER_WIDTH) / 2, 2);
-        self.gameover_speed = Vec2::xy(1, 1);
-    }
-
-    pub fn draw(&mut self, pencil: &mut Pencil) {
-        match self.running {
-            RunningState::Running => self.draw_running(pencil),
-            RunningState::GameOver => self.draw_gameover(pencil),
-        }
-    }
-
-    fn draw_gameover(&mut self, pencil: &mut Pencil) {
-        self.draw_running(pencil);
-        pencil
-            .set_foreground(Color::Xterm(230))
-            .set_background(Color::Xterm(100));
-        let Vec2 { x, y } = self.gameover_pos;
-        pencil.draw_text("           ", self.tx_to_grid(x, y));
-        pencil.draw_text(" GAME OVER ", self.tx_to_grid(x, y + 1));
-        pencil.draw_text("           ", self.tx_to_grid(x, y + 2));
-    }
-
-    fn draw_running(&mut self, pencil: &mut Pencil) {
-        // instructions
-        let mut y = 0;
-        pencil.set_foreground(Color::White);
-        pencil.draw_text(&format!("left/right: move"), self.tx_to_grid(-25, y));
-        y += 1;
-        pencil.draw_text(&format!("up/space: rotate"), self.tx_to_grid(-25, y));
-        y += 1;
-        pencil.draw_text(&format!("down/return: drop"), self.tx_to_grid(-25, y));
-        y += 2;
-        pencil.draw_text(&format!("q/esc: quit"), self.tx_to_grid(-25, y));
-
-        // score
-        y = 0;
-        pencil.set_foreground(Color::White);
-        pencil.draw_text(
-            &format!("lines: {}", self.lines_cleared),
-            self.tx_to_grid(GRID_WIDTH * 2 + 4, y),
-        );
-        y += 2;
-
-        pencil.draw_text(
-            &format!("score: {}", self.score),
-            self.tx_to_grid(GRID_WIDTH * 2 + 4, y),
-        );
-        y += 2;
-
-        pencil.draw_text(
-            &format!("level: {}", self.level),
-            self.tx_to_grid(GRID_WIDTH * 2 + 4, y),
-        );
-        y += 2;
-
-        pencil.draw_text("next pieces:", self.tx_to_grid(GRID_WIDTH * 2 + 4, y));
-        y += 2;
-
-        for tetromino in self.next_pieces.clone().iter() {
-            let mut piece = Piece::new(*tetromino);
-            // check the 'exact' size of the pieces
-            let cells = piece.cells();
-            let min_y = cells.first().unwrap().y;
-            let max_y = cells.last().unwrap().y;
-            y -= min_y;
-            piece.pos = self.tx_to_grid(GRID_WIDTH * 2 + 6, y);
-            y += max_y + 2;
-            self.draw_piece(pencil, &piece);
-        }
-
-        // draw border
-        pencil
-            .set_background(Color::Black)
-            .set_foreground(Color::Xterm(250));
-        pencil.draw_vline('|', self.tx_to_grid(-1, 0), GRID_HEIGHT);
-        pencil.draw_vline('|', self.tx_to_grid(GRID_WIDTH * 2, 0), GRID_HEIGHT);
-        pencil.draw_hline('-', self.tx_to_grid(0, GRID_HEIGHT), GRID_WIDTH * 2);
-        pencil.draw_text("+", self.tx_to_grid(-1, GRID_HEIGHT));
-        pencil.draw_text("+", self.tx_to_grid(GRID_WIDTH * 2, GRID_HEIGHT));
-
-        // draw grid
-        pencil.set_foreground(Color::Xterm(240));
-        for (y, row) in self.grid.iter().enumerate() {
-            let y = y as i32;
-            for (x, cell) in row.iter().enumerate() {
-                let x = x as i32 * 2;
-                let pos = self.tx_to_grid(x, y);
-                match cell {
-                    Cell::Empty => pencil.set_background(Color::Black).draw_text("∙∙", pos),
-                    Cell::Tetromino(tetromino) => pencil
-                        .set_background(tetromino.color())
-                        .draw_text("  ", pos),
-                    Cell::Shadow => pencil
-                        .set_background(Color::Xterm(237))
-                        .draw_text("∙∙", pos),
-                };
-            }
-        }
+    // keep garbage_height rows of garbage on the board, as long as there is some left
+    fn refill_garbage(&mut self) {
+        let missing = self.rules.garbage_height - self.board.garbage_rows();
+        let rows = missing.clamp(0, self.garbage_queue);
+        self.garbage_queue -= rows;
+        self.add_garbage(rows);
     }
 
     //--------------------------------------------------------------------------------
-    // helpers
+    // simulation
     //--------------------------------------------------------------------------------
 
-    fn tx_to_grid(&self, x: i32, y: i32) -> Vec2 {
-        return Vec2::xy(x + self.grid_pos.x, y + self.grid_pos.y);
+    // dt is the (real) time elapsed since the previous call, the game itself
+    // always advances by fixed frames so it runs at the same speed everywhere
+    pub fn update(&mut self, dt: Duration) {
+        // time spent paused is simply lost
+        if self.running != RunningState::Running {
+            return;
+        }
+        self.time_acc += dt.min(MAX_UPDATE_TIME);
+        while self.time_acc >= FRAME {
+            self.time_acc -= FRAME;
+            self.tick();
+        }
     }
 
-    fn is_in_grid(&self, pos: &Vec2) -> bool {
-        pos.x >= 0 && pos.x < GRID_WIDTH && pos.y >= 0 && pos.y < GRID_HEIGHT
+    // advance the game by exactly one frame
+    pub fn tick(&mut self) {
+        if self.running != RunningState::Running {
+            return;
+        }
+
+        self.update_auto_shift(FRAME);
+        self.update_phase();
+
+        if self.phase == Phase::Falling && self.current_piece.is_some() {
+            self.apply_gravity();
+
+            // piece reached the bottom, it locks once the lock delay runs out
+            if self.is_current_piece_grounded() {
+                self.lock_timer += FRAME;
+                if self.lock_timer >= self.rules.lock_delay {
+                    self.lock_current_piece();
+                }
+            }
+        }
+
+        self.update_level();
+        self.frame += 1;
+
+        // time goals are checked on every frame, line goals only when pieces lock
+        if let Some(Goal::Time(_)) = self.rules.goal {
+            if self.running == RunningState::Running && self.is_goal_reached(&[]) {
+                self.gameover(EndReason::Completed);
+            }
+        }
     }
 
-    fn is_in_empty_pos(&self, pos: &Vec2) -> bool {
-        let cell = self.grid[pos.y as usize][pos.x as usize];
-        cell == Cell::Empty || cell == Cell::Shadow
+    // in cells per frame
+    pub fn gravity(&self) -> f64 {
+        match self.rules.gravity {
+            // the original speeds: one row every (11 - level) frames at 30 fps
+            Gravity::Classic => 1.0 / (2 * (11 - self.level).max(1)) as f64,
+            // (0.8 - (level - 1) * 0.007) ^ (level - 1) seconds per row
+            Gravity::Guideline => {
+                let level = self.level.min(20) as f64 - 1.0;
+                let seconds_per_row = (0.8 - level * 0.007).powf(level);
+                (1.0 / (seconds_per_row * FRAME_RATE as f64)).min(GRAVITY_20G)
+            }
+            Gravity::Fixed(gravity) => gravity,
+        }
     }
 
-    fn copy_row_down(&mut self, row: i32) {
-        self.grid[(row + 1) as usize] = self.grid[row as usize].clone();
+    // the level the goal has been met for, before the max level
+    fn reached_level(&self) -> i32 {
+        match self.rules.level_goal {
+            LevelGoal::Points(points) => 1 + self.clear_points / points.max(1),
+            LevelGoal::Lines(lines) => 1 + self.lines_cleared / lines.max(1),
+            // 5 lines for level 1, 10 more for level 2... so 5 * n * (n + 1) / 2 to leave level n
+            LevelGoal::Variable => {
+                let mut level = 1;
+                while self.goal_lines >= 5 * level * (level + 1) / 2 {
+                    level += 1;
+                }
+                level
+            }
+        }
     }
 
-    fn clear_row(&mut self, row: i32) {
-        for x in 0..GRID_WIDTH {
-            self.grid[row as usize][x as usize] = Cell::Empty;
+    fn update_level(&mut self) {
+        // the goal level is the last one, unless the game went on past it
+        let max_level = match self.rules.goal {
+            Some(Goal::Level(level)) if self.continued_at.is_none() => {
+                level.min(self.rules.max_level)
+            }
+            _ => self.rules.max_level,
+        };
+        let level = self.reached_level().clamp(1, max_level.max(1));
+        if level > self.level {
+            self.events.push(Event::LevelUp(level));
         }
+        self.level = level;
     }
 
-    fn is_row_full(&self, row: i32) -> bool {
-        for x in 0..GRID_WIDTH {
-            if self.grid[row as usize][x as usize] == Cell::Empty {
-                return false;
+    fn apply_gravity(&mut self) {
+        let mut gravity = self.gravity();
+        if self.soft_drop {
+            gravity *= self.handling.soft_drop_factor.max(1) as f64;
+        }
+
+        self.gravity_acc += gravity;
+        while self.gravity_acc >= 1.0 {
+            self.gravity_acc -= 1.0;
+            if !self.move_current_piece(Pos::xy(0, 1)) {
+                self.gravity_acc = 0.0;
+                break;
+            }
+            if self.soft_drop {
+                self.score += self.rules.scoring.soft_drop;
             }
         }
-        true
     }
 
-    fn is_piece_in_empty_pos(&self, piece: &Piece) -> bool {
-        for cell in piece.cells().iter() {
-            if !self.is_in_empty_pos(&(piece.pos + *cell)) {
-                return false;
+    fn lock_current_piece(&mut self) {
+        let Some(piece) = self.current_piece.take() else {
+            return;
+        };
+        let spin = detect_spin(&self.board, &piece, self.last_rotation, self.rules.all_spin);
+        self.board.place_piece(&piece);
+        self.can_hold = true;
+        self.stats.pieces += 1;
+        self.events.push(Event::PieceLocked(piece));
+
+        // lock out: the whole piece is hidden above the skyline
+        if piece.board_cells().into_iter().all(|pos| pos.y < 0) {
+            self.gameover(EndReason::LockOut);
+            return;
+        }
+
+        // check if we have a full rows, they only go away after the line clear delay
+        let full_rows = self.board.full_rows();
+        let nb_cleared = full_rows.len() as i32;
+        if nb_cleared > 0 {
+            self.combo += 1;
+        } else {
+            self.combo = -1;
+        }
+
+        if spin != Spin::None {
+            self.events.push(Event::Spin {
+                tetromino: piece.tetromino,
+                spin,
+                lines: nb_cleared,
+            });
+        }
+
+        if nb_cleared > 0 || spin != Spin::None {
+            let clear = LineClear {
+                lines: nb_cleared,
+                spin,
+                back_to_back: self.back_to_back,
+                combo: self.combo.max(0),
+                perfect_clear: nb_cleared > 0 && self.board.is_empty_without(&full_rows),
+            };
+            // spins without lines neither start nor break a chain
+            if nb_cleared > 0 {
+                self.back_to_back = clear.is_difficult();
+            }
+
+            let points = self.rules.scoring.line_clear(&clear, self.level);
+            self.score += points;
+            self.clear_points += points;
+            self.goal_lines += clear.goal_lines();
+            self.stats.add_clear(&clear);
+            if clear.perfect_clear {
+                self.events.push(Event::PerfectClear);
+            }
+            if nb_cleared > 0 {
+                self.lines_cleared += nb_cleared;
+                self.events.push(Event::LinesCleared { clear, points });
             }
         }
-        true
+
+        // the clock stops right away, no need to wait for the rows to go
+        if self.is_goal_reached(&full_rows) {
+            self.board.clear_rows(&full_rows);
+            self.gameover(EndReason::Completed);
+            return;
+        }
+
+        self.clearing_rows = full_rows;
+        if self.clearing_rows.is_empty() {
+            self.enter_phase(Phase::Entry);
+        } else {
+            self.enter_phase(Phase::LineClear);
+        }
     }
 
-    fn is_piece_in_grid(&self, piece: &Piece) -> bool {
-        for cell in piece.cells().iter() {
-            if !self.is_in_grid(&(piece.pos + *cell)) {
-                return false;
+    // zero length phases are skipped right away
+    fn enter_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+        self.phase_timer = Duration::ZERO;
+        match phase {
+            Phase::LineClear if self.rules.line_clear_delay.is_zero() => self.end_phase(),
+            Phase::Entry if self.rules.entry_delay.is_zero() => self.end_phase(),
+            _ => (),
+        }
+    }
+
+    fn end_phase(&mut self) {
+        match self.phase {
+            Phase::Falling => (),
+            Phase::LineClear => {
+                let rows = mem::take(&mut self.clearing_rows);
+                self.board.clear_rows(&rows);
+                self.refill_garbage();
+                self.enter_phase(Phase::Entry);
+            }
+            Phase::Entry => {
+                self.phase = Phase::Falling;
+                self.spawn_next_piece();
+            }
+        }
+    }
+
+    fn update_phase(&mut self) {
+        let delay = match self.phase {
+            Phase::Falling => return,
+            Phase::LineClear => self.rules.line_clear_delay,
+            Phase::Entry => self.rules.entry_delay,
+        };
+        self.phase_timer += FRAME;
+        if self.phase_timer >= delay {
+            self.end_phase();
+        }
+    }
+
+    // full_rows are about to be cleared
+    fn is_goal_reached(&self, full_rows: &[i32]) -> bool {
+        if self.continued_at.is_some() {
+            return false;
+        }
+        match self.rules.goal {
+            None => false,
+            Some(Goal::Lines(lines)) => self.lines_cleared >= lines,
+            Some(Goal::Time(time)) => self.elapsed() >= time,
+            Some(Goal::Level(level)) => self.reached_level() > level,
+            Some(Goal::Garbage) => {
+                self.garbage_queue == 0 && self.board.garbage_rows_without(full_rows) == 0
             }
         }
-        true
     }
 
-    fn pick_current_piece(&mut self) -> Piece {
+    fn gameover(&mut self, reason: EndReason) {
+        if reason == EndReason::Completed {
+            self.completed_score = Some(self.score);
+        }
+        self.running = RunningState::GameOver;
+        self.end_reason = Some(reason);
+        self.events.push(Event::GameOver(reason));
+    }
+
+    //--------------------------------------------------------------------------------
+    // helpers
+    //--------------------------------------------------------------------------------
+
+    fn spawn_next_piece(&mut self) {
         let tetromino = self.next_pieces.remove(0);
-        self.next_pieces.push(Tetromino::random());
+        self.next_pieces.push(self.randomizer.next_piece());
+        self.spawn_piece(tetromino);
+    }
+
+    fn spawn_piece(&mut self, tetromino: Tetromino) {
         let mut piece = Piece::new(tetromino);
         let cells = piece.cells();
         let min_y = cells.first().unwrap().y;
-        piece.pos = Vec2::xy(GRID_WIDTH / 2 - 1, -min_y);
-        self.current_piece = Some(piece);
-        self.drop_current_piece = false;
-        piece
-    }
-
-    fn place_piece(&mut self, piece: &Piece, as_shadow: bool) {
-        for cell in piece.cells().iter() {
-            let x = piece.pos.x + cell.x;
-            let y = piece.pos.y + cell.y;
-            if x >= 0 && x < GRID_WIDTH && y >= 0 && y < GRID_HEIGHT {
-                self.grid[y as usize][x as usize] = if as_shadow {
-                    Cell::Shadow
-                } else {
-                    Cell::Tetromino(piece.tetromino)
-                }
+        // centered, rounding to the left (columns 4 to 6 of 10 for the 3 wide pieces),
+        // in the 2 rows right above the skyline (or at the very top without a buffer)
+        let top = -self.board.buffer().min(2);
+        piece.pos = Pos::xy((self.board.width() - 3) / 2, top - min_y);
+
+        // block out: there's no room for the new piece
+        if !self.board.fits(&piece) {
+            self.gameover(EndReason::BlockOut);
+            return;
+        }
+
+        // and it drops one row right away if it can
+        if top < 0 {
+            let mut below = piece;
+            below.pos.y += 1;
+            if self.board.fits(&below) {
+                piece = below;
             }
         }
+
+        self.current_piece = Some(piece);
+        self.last_rotation = None;
+        self.lock_timer = Duration::ZERO;
+        self.lock_resets = 0;
+        self.lowest_row = bottom_row(&piece);
+        self.gravity_acc = 0.0;
+        self.events.push(Event::PieceSpawned(tetromino));
     }
 
-    fn draw_piece(&mut self, pencil: &mut Pencil, piece: &Piece) {
-        pencil.set_background(piece.tetromino.color());
-        for cell in piece.cells().iter() {
-            let x = piece.pos.x + cell.x * 2;
-            let y = piece.pos.y + cell.y;
-            pencil.draw_text("  ", Vec2::xy(x, y));
+    // drop the piece straight to its ghost position and lock it right away
+    fn hard_drop_current_piece(&mut self) -> bool {
+        let (Some(piece), Some(ghost)) = (self.current_piece, self.ghost_piece()) else {
+            return false;
+        };
+
+        let rows = ghost.pos.y - piece.pos.y;
+        if rows > 0 {
+            self.current_piece = Some(ghost);
+            self.last_rotation = None;
         }
+        self.score += rows * self.rules.scoring.hard_drop;
+        self.events.push(Event::HardDropped { piece: ghost, rows });
+        self.lock_current_piece();
+        true
     }
 
-    fn remove_piece(&mut self, piece: &Piece) {
-        for cell in piece.cells().iter() {
-            let x = piece.pos.x + cell.x;
-            let y = piece.pos.y + cell.y;
-            if x >= 0 && x < GRID_WIDTH && y >= 0 && y < GRID_HEIGHT {
-                self.grid[y as usize][x as usize] = Cell::Empty;
+    fn is_current_piece_grounded(&self) -> bool {
+        self.current_piece.is_some_and(|piece| {
+            let mut below = piece;
+            below.pos.y += 1;
+            !self.board.fits(&below)
+        })
+    }
+
+    // called after every successful move or rotation of the current piece
+    fn on_piece_moved(&mut self, moved_down: bool) {
+        let Some(piece) = self.current_piece else {
+            return;
+        };
+
+        // reaching a new lowest row gives a fresh lock delay
+        let row = bottom_row(&piece);
+        if row > self.lowest_row {
+            self.lowest_row = row;
+            self.lock_timer = Duration::ZERO;
+            self.lock_resets = 0;
+            return;
+        }
+
+        // moves while in the air don't use up resets
+        let touched_down = self.lock_timer > Duration::ZERO || self.is_current_piece_grounded();
+        match self.rules.lock_reset {
+            LockReset::Move(max_resets) => {
+                if touched_down && self.lock_resets < max_resets {
+                    self.lock_timer = Duration::ZERO;
+                    self.lock_resets += 1;
+                }
+            }
+            LockReset::Infinity => self.lock_timer = Duration::ZERO,
+            LockReset::Step => {
+                if moved_down {
+                    self.lock_timer = Duration::ZERO;
+                }
             }
         }
     }
 
-    // fn move_gpos(&mut self, delta: Vec2) {
-    //     self.grid_pos += delta;
-    //     self.grid_pos.x = max(0, self.grid_pos.x);
-    //     self.grid_pos.y = max(0, self.grid_pos.y);
-    //     self.grid_pos.x = min(self.dimension.x - GRID_WIDTH * 2, self.grid_pos.x);
-    //     self.grid_pos.y = min(self.dimension.y - GRID_WIDTH * 2, self.grid_pos.y);
-    // }
+    fn hold_current_piece(&mut self) -> bool {
+        if !self.can_hold() {
+            return false;
+        }
+        let Some(piece) = self.current_piece.take() else {
+            return false;
+        };
 
-    fn drop_current_piece(&mut self) {
-        self.drop_current_piece = true;
+        self.can_hold = false;
+        self.events.push(Event::PieceHeld(piece.tetromino));
+        match self.hold_piece.replace(piece.tetromino) {
+            Some(held) => self.spawn_piece(held),
+            None => self.spawn_next_piece(),
+        }
+        true
     }
 
-    fn move_current_piece(&mut self, delta: Vec2) -> bool {
+    fn move_current_piece(&mut self, delta: Pos) -> bool {
         if let Some(piece) = self.current_piece {
-            // let's first make sure we remove the current piece from the grid
-            self.remove_piece(&piece);
-
-            let mut new_piece = piece.clone();
+            let mut new_piece = piece;
             new_piece.pos += delta;
-            if self.is_piece_in_grid(&new_piece) && self.is_piece_in_empty_pos(&new_piece) {
+            if self.board.fits(&new_piece) {
                 self.current_piece = Some(new_piece);
+                self.last_rotation = None;
+                self.on_piece_moved(delta.y > 0);
                 return true;
             }
         }
-        return false;
+        false
     }
 
-    fn rotate_current_piece(&mut self) -> bool {
-        if let Some(piece) = self.current_piece {
-            // let's first make sure we remove the current piece from the grid
-            self.remove_piece(&piece);
-
-            let mut new_piece = piece.clone();
-            new_piece.rotate(1);
+    // returns the index of the kick that made the rotation possible
+    fn rotate_current_piece(&mut self, delta: i32) -> Option<usize> {
+        let piece = self.current_piece?;
+        let mut rotated = piece;
+        rotated.rotate(delta);
 
-            if self.is_piece_in_grid(&new_piece) && self.is_piece_in_empty_pos(&new_piece) {
+        for (kick, offset) in kicks(piece.tetromino, piece.rot, delta)
+            .into_iter()
+            .enumerate()
+        {
+            let mut new_piece = rotated;
+            new_piece.pos += offset;
+            if self.board.fits(&new_piece) {
                 self.current_piece = Some(new_piece);
-                return true;
+                self.last_rotation = Some((delta, kick));
+                self.on_piece_moved(false);
+                self.events.push(Event::PieceRotated {
+                    piece: new_piece,
+                    kick,
+                });
+                return Some(kick);
             }
         }
-        return false;
-    }
-
-    fn init_with_all_pieces(&mut self) {
-        for (t_nb, t) in [
-            Tetromino::I,
-            Tetromino::J,
-            Tetromino::L,
-            Tetromino::O,
-            Tetromino::S,
-            Tetromino::T,
-            Tetromino::Z,
-        ]
-        .iter()
-        .enumerate()
-        {
-            for rot in 0..=3 {
-                let mut piece = Piece::new(*t);
-                piece.pos = Vec2::xy(rot * 5, t_nb as i32 * 5);
-                piece.rotate(rot);
-                self.place_piece(&piece, true);
+        None
+    }
+}
+
+// highest row covered by the piece (remember y goes down)
+fn top_row(piece: &Piece) -> i32 {
+    piece
+        .board_cells()
+        .into_iter()
+        .map(|pos| pos.y)
+        .min()
+        .unwrap_or(piece.pos.y)
+}
+
+// lowest row covered by the piece (remember y goes down)
+fn bottom_row(piece: &Piece) -> i32 {
+    piece
+        .board_cells()
+        .into_iter()
+        .map(|pos| pos.y)
+        .max()
+        .unwrap_or(piece.pos.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+
+    fn game(rules: Rules) -> GameState {
+        let mut state = GameState::with_rules(1, rules);
+        state.init();
+        state
+    }
+
+    fn current(state: &GameState) -> Piece {
+        state.current_piece().unwrap()
+    }
+
+    #[test]
+    fn one_hold_per_piece() {
+        let mut state = game(Rules::default());
+        let first = current(&state).tetromino;
+        let next = state.next_pieces()[0];
+
+        assert!(state.press(Input::Hold));
+        assert_eq!(state.hold_piece(), Some(first));
+        assert_eq!(current(&state).tetromino, next);
+        assert!(state.take_events().contains(&Event::PieceHeld(first)));
+
+        // not again until the piece locks
+        assert!(!state.press(Input::Hold));
+        assert_eq!(current(&state).tetromino, next);
+
+        state.press(Input::HardDrop);
+        let third = current(&state).tetromino;
+        assert!(state.press(Input::Hold));
+        assert_eq!(current(&state).tetromino, first);
+        assert_eq!(state.hold_piece(), Some(third));
+    }
+
+    #[test]
+    fn hard_drop_scores_and_locks_right_away() {
+        let mut state = game(Rules::default());
+        let rows = state.ghost_piece().unwrap().pos.y - current(&state).pos.y;
+        assert!(rows > 0);
+
+        assert!(state.press(Input::HardDrop));
+        assert_eq!(state.score(), rows * 2);
+        assert_eq!(state.stats().pieces, 1);
+        let events = state.take_events();
+        assert!(events.iter().any(
+            |event| matches!(event, Event::HardDropped { rows: dropped, .. } if *dropped == rows)
+        ));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::PieceLocked(_))));
+        // no lock delay, the next piece is already there
+        assert_eq!(state.frame(), 0);
+        assert!(state.current_piece().is_some());
+    }
+
+    // frame the first piece locks on, at 20G, shifting it back and forth after the given frames
+    fn lock_frame(lock_reset: LockReset, moves: &[u64]) -> u64 {
+        let mut state = game(Rules {
+            gravity: Gravity::Fixed(GRAVITY_20G),
+            lock_reset,
+            ..Rules::default()
+        });
+        while state.stats().pieces == 0 {
+            state.tick();
+            if let Some(i) = moves.iter().position(|frame| *frame == state.frame()) {
+                let input = [Input::MoveLeft, Input::MoveRight][i % 2];
+                assert!(state.press(input));
+                state.release(input);
             }
         }
+        state.frame()
+    }
+
+    #[test]
+    fn lock_delay() {
+        // grounded on the first frame, 500ms later it locks
+        let delay = lock_frame(LockReset::Move(15), &[]);
+        assert_eq!(delay, 500_000_000u64.div_ceil(FRAME.as_nanos() as u64));
+    }
+
+    #[test]
+    fn move_reset_is_capped() {
+        let delay = lock_frame(LockReset::Move(15), &[]);
+        // the third move is one too many
+        assert_eq!(lock_frame(LockReset::Move(2), &[10, 20, 30]), 20 + delay);
+        assert_eq!(lock_frame(LockReset::Move(15), &[10, 20, 30]), 30 + delay);
+    }
+
+    #[test]
+    fn infinity_and_step_reset() {
+        let delay = lock_frame(LockReset::Move(15), &[]);
+        let moves: Vec<u64> = (1..=20).map(|i| i * 10).collect();
+        assert_eq!(lock_frame(LockReset::Infinity, &moves), 200 + delay);
+        // moving sideways doesn't count as a step
+        assert_eq!(lock_frame(LockReset::Step, &moves), delay);
+    }
+
+    fn with_handling(rules: Rules, das: u64, arr: u64) -> GameState {
+        let mut state = GameState::with_rules(1, rules);
+        state.set_handling(Handling {
+            das: Duration::from_millis(das),
+            arr: Duration::from_millis(arr),
+            ..Handling::default()
+        });
+        state.init();
+        state
+    }
+
+    // as far right as the current piece goes
+    fn right_wall(state: &GameState) -> i32 {
+        let mut piece = current(state);
+        while state.board().fits(&piece) {
+            piece.pos.x += 1;
+        }
+        piece.pos.x - 1
+    }
+
+    #[test]
+    fn das_charges_before_repeating() {
+        let rules = Rules {
+            gravity: Gravity::Fixed(0.0),
+            ..Rules::default()
+        };
+        let mut state = with_handling(rules, 100, 50);
+        let x = current(&state).pos.x;
+
+        state.press(Input::MoveRight);
+        assert_eq!(current(&state).pos.x, x + 1);
+        // 100ms is 6 frames and a bit
+        for _ in 0..6 {
+            state.tick();
+        }
+        assert_eq!(current(&state).pos.x, x + 1);
+        state.tick();
+        assert_eq!(current(&state).pos.x, x + 2);
+        // then every 50ms (3 frames and a bit)
+        for _ in 0..3 {
+            state.tick();
+        }
+        assert_eq!(current(&state).pos.x, x + 2);
+        state.tick();
+        assert_eq!(current(&state).pos.x, x + 3);
+    }
+
+    #[test]
+    fn arr_0_goes_to_the_wall() {
+        let rules = Rules {
+            gravity: Gravity::Fixed(0.0),
+            ..Rules::default()
+        };
+        let mut state = with_handling(rules, 100, 0);
+        state.press(Input::MoveRight);
+        for _ in 0..7 {
+            state.tick();
+        }
+        assert_eq!(current(&state).pos.x, right_wall(&state));
+    }
+
+    #[test]
+    fn das_charges_during_entry_delay() {
+        let rules = Rules {
+            gravity: Gravity::Fixed(0.0),
+            entry_delay: Duration::from_millis(500),
+            ..Rules::default()
+        };
+        let mut state = with_handling(rules, 100, 0);
+        state.press(Input::HardDrop);
+        assert!(state.current_piece().is_none());
+
+        state.press(Input::MoveRight);
+        while state.current_piece().is_none() {
+            state.tick();
+        }
+        // already charged, the new piece shifts on its first frame
+        state.tick();
+        assert_eq!(current(&state).pos.x, right_wall(&state));
+    }
+
+    #[test]
+    fn fractional_gravity() {
+        let mut state = game(Rules {
+            gravity: Gravity::Fixed(0.5),
+            ..Rules::default()
+        });
+        let y = current(&state).pos.y;
+        state.tick();
+        assert_eq!(current(&state).pos.y, y);
+        state.tick();
+        assert_eq!(current(&state).pos.y, y + 1);
+        for _ in 0..4 {
+            state.tick();
+        }
+        assert_eq!(current(&state).pos.y, y + 3);
+    }
+
+    #[test]
+    fn gravity_20g_lands_right_away() {
+        let mut state = game(Rules {
+            gravity: Gravity::Fixed(GRAVITY_20G),
+            ..Rules::default()
+        });
+        let ghost = state.ghost_piece();
+        state.tick();
+        assert_eq!(state.current_piece(), ghost);
+    }
+
+    #[test]
+    fn guideline_gravity_reaches_20g() {
+        let mut state = game(Rules {
+            gravity: Gravity::Guideline,
+            ..Rules::default()
+        });
+        // a row per second at level 1
+        assert_eq!(state.gravity(), 1.0 / FRAME_RATE as f64);
+        state.level = 15;
+        assert!(state.gravity() < GRAVITY_20G);
+        state.level = 20;
+        assert_eq!(state.gravity(), GRAVITY_20G);
+    }
+
+    // fill a row with locked cells, but for some columns
+    fn fill_row(state: &mut GameState, y: i32, except: &[i32]) {
+        for x in 0..state.board.width() {
+            if !except.contains(&x) {
+                state
+                    .board
+                    .set_cell(Pos::xy(x, y), Cell::Tetromino(Tetromino::O));
+            }
+        }
+    }
+
+    // the columns covered by the current piece
+    fn columns(state: &GameState) -> Vec<i32> {
+        current(state)
+            .board_cells()
+            .into_iter()
+            .map(|pos| pos.x)
+            .collect()
+    }
+
+    #[test]
+    fn perfect_clear() {
+        let mut state = game(Rules::default());
+        state.spawn_piece(Tetromino::I);
+        let bottom = state.board.height() - 1;
+        let gap = columns(&state);
+        fill_row(&mut state, bottom, &gap);
+        state.take_events();
+
+        state.press(Input::HardDrop);
+        let events = state.take_events();
+        assert!(events.contains(&Event::PerfectClear));
+        let points = events.iter().find_map(|event| match event {
+            Event::LinesCleared { clear, points } => Some((clear.perfect_clear, *points)),
+            _ => None,
+        });
+        // 100 for the single and 800 on top for emptying the board
+        assert_eq!(points, Some((true, 900)));
+        assert!(state
+            .board()
+            .rows()
+            .flatten()
+            .all(|cell| *cell == Cell::Empty));
+        assert_eq!(state.stats().perfect_clears, 1);
+    }
+
+    #[test]
+    fn block_out() {
+        // the stack reaches into the spawn rows
+        let mut state = GameState::with_rules(1, Rules::default());
+        for y in -2..state.board.height() {
+            fill_row(&mut state, y, &[9]);
+        }
+        state.init();
+        assert!(state.is_game_over());
+        assert_eq!(state.end_reason(), Some(EndReason::BlockOut));
+        assert!(state.current_piece().is_none());
+    }
+
+    #[test]
+    fn lock_out() {
+        // the stack reaches the skyline, the piece spawns above it and can't go lower
+        let mut state = GameState::with_rules(1, Rules::default());
+        for y in 0..state.board.height() {
+            fill_row(&mut state, y, &[9]);
+        }
+        state.init();
+        assert!(state.current_piece().is_some());
+
+        state.press(Input::HardDrop);
+        assert_eq!(state.end_reason(), Some(EndReason::LockOut));
+        assert!(state
+            .take_events()
+            .contains(&Event::GameOver(EndReason::LockOut)));
+    }
+
+    // frames a delay lasts, they are counted in whole frames
+    fn frames(delay: Duration) -> u64 {
+        delay.as_nanos().div_ceil(FRAME.as_nanos()) as u64
+    }
+
+    #[test]
+    fn line_clear_and_entry_delays() {
+        let line_clear_delay = Duration::from_millis(250);
+        let entry_delay = Duration::from_millis(100);
+        let mut state = game(Rules {
+            gravity: Gravity::Fixed(0.0),
+            line_clear_delay,
+            entry_delay,
+            ..Rules::default()
+        });
+        state.spawn_piece(Tetromino::I);
+        let bottom = state.board.height() - 1;
+        let gap = columns(&state);
+        fill_row(&mut state, bottom, &gap);
+
+        // the full row stays on the board for the line clear delay
+        state.press(Input::HardDrop);
+        assert_eq!(state.phase(), Phase::LineClear);
+        assert_eq!(state.clearing_rows(), [bottom]);
+        assert!(state.board().is_row_full(bottom));
+        for _ in 1..frames(line_clear_delay) {
+            state.tick();
+        }
+        assert_eq!(state.phase(), Phase::LineClear);
+        state.tick();
+        assert_eq!(state.phase(), Phase::Entry);
+        assert!(state.board().is_row_empty(bottom));
+        assert!(state.current_piece().is_none());
+
+        // then no piece for the entry delay
+        for _ in 1..frames(entry_delay) {
+            state.tick();
+        }
+        assert_eq!(state.phase(), Phase::Entry);
+        state.take_events();
+        state.tick();
+        assert_eq!(state.phase(), Phase::Falling);
+        assert!(state.current_piece().is_some());
+        assert!(matches!(
+            state.take_events()[..],
+            [Event::PieceSpawned(_), ..]
+        ));
+    }
+
+    // single with an I piece into a row left open for it
+    fn clear_single(state: &mut GameState) {
+        state.spawn_piece(Tetromino::I);
+        let bottom = state.board.height() - 1;
+        let gap = columns(state);
+        fill_row(state, bottom, &gap);
+        state.press(Input::HardDrop);
+    }
+
+    #[test]
+    fn line_goal() {
+        let mut state = game(Rules {
+            line_clear_delay: Duration::from_millis(250),
+            goal: Some(Goal::Lines(2)),
+            ..Rules::default()
+        });
+        clear_single(&mut state);
+        while state.phase() != Phase::Falling {
+            state.tick();
+        }
+        assert!(!state.is_game_over());
+
+        // the clock stops on the lock, the last rows don't wait for the delay
+        clear_single(&mut state);
+        assert_eq!(state.end_reason(), Some(EndReason::Completed));
+        assert!(state
+            .board()
+            .rows()
+            .flatten()
+            .all(|cell| *cell == Cell::Empty));
+    }
+
+    #[test]
+    fn time_goal() {
+        let mut state = game(Rules {
+            gravity: Gravity::Fixed(0.0),
+            goal: Some(Goal::Time(Duration::from_secs(1))),
+            ..Rules::default()
+        });
+        for _ in 1..FRAME_RATE {
+            state.tick();
+        }
+        assert!(!state.is_game_over());
+        assert!(state.remaining_time().is_some_and(|time| !time.is_zero()));
+
+        state.tick();
+        assert_eq!(state.end_reason(), Some(EndReason::Completed));
+        assert_eq!(state.remaining_time(), Some(Duration::ZERO));
+        assert_eq!(state.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn level_goal_and_keeping_going() {
+        let mut state = game(Rules {
+            level_goal: LevelGoal::Lines(1),
+            max_level: 20,
+            goal: Some(Goal::Level(2)),
+            ..Rules::default()
+        });
+        clear_single(&mut state);
+        state.tick();
+        assert_eq!(state.level(), 2);
+        assert!(!state.is_game_over());
+
+        clear_single(&mut state);
+        state.tick();
+        assert_eq!(state.end_reason(), Some(EndReason::Completed));
+        assert_eq!(state.level(), 2);
+        assert_eq!(state.completed_score(), Some(state.score()));
+
+        assert!(state.keep_going());
+        assert!(!state.can_keep_going());
+        assert!(state.current_piece().is_some());
+        state.tick();
+        assert_eq!(state.level(), 3);
+        clear_single(&mut state);
+        assert!(!state.is_game_over());
+        // the score at the goal is kept aside
+        assert!(state.completed_score() < Some(state.score()));
+    }
+
+    #[test]
+    fn variable_goal_counts_awarded_lines() {
+        let mut state = game(Rules {
+            level_goal: LevelGoal::Variable,
+            goal: Some(Goal::Level(1)),
+            ..Rules::default()
+        });
+        // a single is 1 of the 5 lines level 1 takes, a tetris is 8
+        clear_single(&mut state);
+        assert!(!state.is_game_over());
+
+        state.spawn_piece(Tetromino::I);
+        state.press(Input::RotateCw);
+        let well = columns(&state);
+        let bottom = state.board.height() - 1;
+        for y in bottom - 3..=bottom {
+            fill_row(&mut state, y, &well);
+        }
+        state.press(Input::HardDrop);
+        assert_eq!(state.end_reason(), Some(EndReason::Completed));
+    }
+
+    #[test]
+    fn garbage_rows_come_in_from_the_bottom() {
+        let mut state = game(Rules {
+            garbage_messiness: 0.0,
+            ..Rules::default()
+        });
+        state.add_garbage(3);
+        assert_eq!(state.board().garbage_rows(), 3);
+        assert_eq!(state.garbage_left(), 3);
+        // no messiness, the holes line up
+        let bottom = state.board.height() - 1;
+        let hole = (0..state.board.width())
+            .find(|x| state.board().is_in_empty_pos(Pos::xy(*x, bottom)))
+            .unwrap();
+        for y in bottom - 2..=bottom {
+            assert!(state.board().is_in_empty_pos(Pos::xy(hole, y)));
+            assert!(!state.board().is_row_empty(y));
+        }
+    }
+
+    #[test]
+    fn garbage_pushes_the_piece_up_then_tops_out() {
+        let mut state = game(Rules::default());
+        let height = state.board.height();
+        state.add_garbage(height);
+        assert!(!state.is_game_over());
+        assert!(state.board().fits(&current(&state)));
+
+        state.add_garbage(state.board.buffer());
+        assert_eq!(state.end_reason(), Some(EndReason::TopOut));
+    }
+
+    #[test]
+    fn garbage_is_refilled_and_dug_through() {
+        let mut state = game(Rules {
+            garbage_rows: 12,
+            garbage_height: 10,
+            goal: Some(Goal::Garbage),
+            ..Rules::default()
+        });
+        assert_eq!(state.board().garbage_rows(), 10);
+        assert_eq!(state.garbage_left(), 12);
+
+        // plug the bottom hole, the row goes with the next piece and another comes in
+        let bottom = state.board.height() - 1;
+        fill_row(&mut state, bottom, &[]);
+        state.press(Input::HardDrop);
+        assert_eq!(state.board().garbage_rows(), 10);
+        assert_eq!(state.garbage_left(), 11);
+
+        // dig out the rest, the game ends with the last garbage row
+        for _ in 0..11 {
+            fill_row(&mut state, bottom, &[]);
+            state.press(Input::HardDrop);
+        }
+        assert_eq!(state.garbage_left(), 0);
+        assert_eq!(state.end_reason(), Some(EndReason::Completed));
     }
 }
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+// player preferences for how held keys repeat, independent of the frame rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Handling {
+    // delayed auto shift: how long left/right must be held before it starts repeating
+    pub das: Duration,
+    // auto repeat rate: time between repeats once charged, zero goes straight to the wall
+    pub arr: Duration,
+    // how many times faster than gravity the piece falls while soft dropping
+    pub soft_drop_factor: i32,
+}
+
+impl Default for Handling {
+    fn default() -> Self {
+        Self {
+            das: Duration::from_millis(167),
+            arr: Duration::from_millis(33),
+            soft_drop_factor: 20,
+        }
+    }
+}
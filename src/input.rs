@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Input {
+    MoveLeft,
+    MoveRight,
+    RotateCw,
+    RotateCcw,
+    Rotate180,
+    SoftDrop,
+    HardDrop,
+    Hold,
+}
@@ -0,0 +1,99 @@
+use crate::pos::Pos;
+use crate::tetromino::Tetromino;
+
+// SRS wall kicks, see: https://tetris.wiki/Super_Rotation_System
+// offsets are written as on the wiki (y up) and flipped when looked up, since our y goes down.
+// each table is indexed by the rotation we start from: 0, R, 2, L
+
+type KickTable = [&'static [(i32, i32)]; 4];
+
+const JLSTZ_CW: KickTable = [
+    &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 0 -> R
+    &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 2
+    &[(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 2 -> L
+    &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 0
+];
+
+const JLSTZ_CCW: KickTable = [
+    &[(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 0 -> L
+    &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 0
+    &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 2 -> R
+    &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 2
+];
+
+const I_CW: KickTable = [
+    &[(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)], // 0 -> R
+    &[(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)], // R -> 2
+    &[(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)], // 2 -> L
+    &[(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)], // L -> 0
+];
+
+const I_CCW: KickTable = [
+    &[(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)], // 0 -> L
+    &[(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)], // R -> 0
+    &[(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)], // 2 -> R
+    &[(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)], // L -> 2
+];
+
+// 180 rotations aren't part of SRS, this is the (SRS+) table used by tetr.io, for every piece
+const FLIP: KickTable = [
+    &[(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)], // 0 -> 2
+    &[(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],   // R -> L
+    &[(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)], // 2 -> 0
+    &[(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)], // L -> R
+];
+
+// the offsets to try, in order, when rotating `tetromino` from `rot` by `delta` quarter turns
+pub fn kicks(tetromino: Tetromino, rot: i32, delta: i32) -> Vec<Pos> {
+    let from = rot.rem_euclid(4) as usize;
+    let table = match (tetromino, delta.rem_euclid(4)) {
+        // the O piece doesn't kick
+        (Tetromino::O, _) => return vec![Pos::zero()],
+        (Tetromino::I, 1) => &I_CW,
+        (Tetromino::I, 3) => &I_CCW,
+        (_, 1) => &JLSTZ_CW,
+        (_, 3) => &JLSTZ_CCW,
+        (_, 2) => &FLIP,
+        _ => return vec![Pos::zero()],
+    };
+    table[from].iter().map(|(x, y)| Pos::xy(*x, -*y)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::piece::Piece;
+
+    #[test]
+    fn offsets_are_flipped_to_board_coordinates() {
+        let expected = [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)].map(|(x, y)| Pos::xy(x, y));
+        assert_eq!(kicks(Tetromino::T, 0, 1), expected);
+        assert_eq!(kicks(Tetromino::I, 0, 1)[1], Pos::xy(-2, 0));
+        assert_eq!(kicks(Tetromino::T, 0, 2).len(), 6);
+        assert_eq!(kicks(Tetromino::O, 1, -1), [Pos::zero()]);
+    }
+
+    #[test]
+    fn wall_kick() {
+        let board = Board::new(10, 20, 0);
+        // pointing right against the left wall, turning back would stick out of it
+        let piece = Piece {
+            tetromino: Tetromino::T,
+            rot: 1,
+            pos: Pos::xy(-1, 5),
+        };
+        let mut rotated = piece;
+        rotated.rotate(-1);
+
+        let kick = kicks(piece.tetromino, piece.rot, -1)
+            .into_iter()
+            .position(|offset| {
+                let mut kicked = rotated;
+                kicked.pos += offset;
+                board.fits(&kicked)
+            });
+        assert_eq!(kick, Some(1));
+        assert_eq!(kicks(piece.tetromino, piece.rot, -1)[1], Pos::xy(1, 0));
+    }
+}
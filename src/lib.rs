@@ -0,0 +1,34 @@
+// headless tetris engine: no terminal involved, feed it inputs and read back events
+
+pub mod board;
+pub mod cell;
+pub mod event;
+pub mod gamestate;
+pub mod handling;
+pub mod input;
+pub mod kicks;
+pub mod piece;
+pub mod pos;
+pub mod randomizer;
+pub mod replay;
+pub mod rules;
+pub mod scoring;
+pub mod spin;
+pub mod stats;
+pub mod tetromino;
+
+pub use board::Board;
+pub use cell::Cell;
+pub use event::Event;
+pub use gamestate::{EndReason, GameState, Phase, RunningState};
+pub use handling::Handling;
+pub use input::Input;
+pub use piece::Piece;
+pub use pos::Pos;
+pub use randomizer::{Randomizer, RandomizerKind};
+pub use replay::{Playback, Replay, ReplayInput};
+pub use rules::{Goal, Gravity, LevelGoal, LockReset, Rules};
+pub use scoring::{LineClear, ScoringTable};
+pub use spin::Spin;
+pub use stats::Stats;
+pub use tetromino::Tetromino;
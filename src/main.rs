@@ -1,29 +1,95 @@
-use gamestate::GameState;
+use std::env;
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
 use ruscii::app::{App, State};
 use ruscii::drawing::Pencil;
 use ruscii::gui::FPSCounter;
 use ruscii::keyboard::{Key, KeyEvent};
 use ruscii::terminal::Window;
+use tetris::Gravity;
+
+use crate::records::Records;
+use crate::ui::screen::{Context, Screen, Transition};
+use crate::ui::settings::Settings;
+use crate::ui::title::TitleScreen;
+
+mod records;
+mod ui;
+
+const USAGE: &str =
+    "usage: tetris [--seed <u64>] [--randomizer uniform|7-bag|14-bag|tgm|nes] [--no-hold] [--all-spin]
+              [--scoring guideline|classic]
+              [--width <cells>] [--height <cells>] [--buffer <rows>]
+              [--are <ms>] [--line-clear-delay <ms>] [--gravity <cells per frame>]
+              [--lock-delay <ms>] [--lock-reset move:<n>|infinity|step]
+              [--das <ms>] [--arr <ms>] [--sdf <factor>] [--clear-animation none|flash|wipe|fade]";
 
-mod cell;
-mod gamestate;
-mod piece;
-mod tetromino;
+fn parse_value<T: FromStr>(it: &mut impl Iterator<Item = String>, name: &str) -> Result<T, String> {
+    let value = it.next().ok_or(format!("{name} needs a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {name}: {value}"))
+}
+
+// starting point for the settings screen
+fn parse_args() -> Result<Settings, String> {
+    let mut args = Settings::default();
+    let mut it = env::args().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--seed" => args.seed = Some(parse_value(&mut it, &arg)?),
+            "--randomizer" => args.rules.randomizer = parse_value(&mut it, &arg)?,
+            "--width" => args.rules.board_width = parse_value(&mut it, &arg)?,
+            "--height" => args.rules.board_height = parse_value(&mut it, &arg)?,
+            "--buffer" => args.rules.buffer_rows = parse_value(&mut it, &arg)?,
+            "--are" => args.rules.entry_delay = Duration::from_millis(parse_value(&mut it, &arg)?),
+            "--line-clear-delay" => {
+                args.rules.line_clear_delay = Duration::from_millis(parse_value(&mut it, &arg)?)
+            }
+            "--lock-delay" => {
+                args.rules.lock_delay = Duration::from_millis(parse_value(&mut it, &arg)?)
+            }
+            "--lock-reset" => args.rules.lock_reset = parse_value(&mut it, &arg)?,
+            "--gravity" => args.rules.gravity = Gravity::Fixed(parse_value(&mut it, &arg)?),
+            "--das" => args.handling.das = Duration::from_millis(parse_value(&mut it, &arg)?),
+            "--arr" => args.handling.arr = Duration::from_millis(parse_value(&mut it, &arg)?),
+            "--sdf" => args.handling.soft_drop_factor = parse_value(&mut it, &arg)?,
+            "--no-hold" => args.rules.hold_enabled = false,
+            "--all-spin" => args.rules.all_spin = true,
+            "--scoring" => args.rules.scoring = parse_value(&mut it, &arg)?,
+            "--clear-animation" => args.clear_animation = parse_value(&mut it, &arg)?,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument: {arg}\n{USAGE}")),
+        }
+    }
+    Ok(args)
+}
 
 fn main() {
+    let settings = parse_args().unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    });
+
     let mut fps_counter = FPSCounter::default();
     let mut app = App::default();
     // let mut app = App::config(Config { fps: 30 });
-    let win_size = app.window().size();
-    let mut state = GameState::new(win_size);
-    state.init();
+    let mut ctx = Context {
+        win_size: app.window().size(),
+        settings,
+        records: Records::load(),
+        replays: vec![],
+    };
+    // the top screen is the one shown, the app stops once they are all gone
+    let mut screens = vec![Screen::Title(TitleScreen::new())];
 
+    // ruscii doesn't report focus changes, so there is no pausing when the terminal loses focus
     app.run(|app_state: &mut State, window: &mut Window| {
         for key_event in app_state.keyboard().last_key_events() {
-            match key_event {
-                KeyEvent::Pressed(Key::Esc) => app_state.stop(),
-                KeyEvent::Pressed(Key::Q) => app_state.stop(),
-                _ => (),
+            if let KeyEvent::Pressed(Key::Q) = key_event {
+                app_state.stop();
             }
         }
 
@@ -31,9 +97,25 @@ fn main() {
         let step = app_state.step();
         let mut pencil = Pencil::new(window.canvas_mut());
 
-        state.set_step(step);
-        state.handle_keys_down(app_state.keyboard().get_keys_down());
-        state.update();
-        state.draw(&mut pencil);
+        let key_events = app_state.keyboard().last_key_events();
+        let Some(screen) = screens.last_mut() else {
+            return;
+        };
+        match screen.handle_key_events(&mut ctx, key_events) {
+            Some(Transition::Push(screen)) => screens.push(screen),
+            Some(Transition::Replace(screen)) => *screens.last_mut().unwrap() = screen,
+            Some(Transition::Pop) => {
+                screens.pop();
+            }
+            Some(Transition::Quit) => screens.clear(),
+            None => (),
+        }
+
+        let Some(screen) = screens.last_mut() else {
+            app_state.stop();
+            return;
+        };
+        screen.update(step);
+        screen.draw(&ctx, &mut pencil);
     });
 }
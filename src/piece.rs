@@ -1,12 +1,11 @@
-use ruscii::spatial::Vec2;
-
+use crate::pos::Pos;
 use crate::tetromino::Tetromino;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Piece {
     pub tetromino: Tetromino,
     pub rot: i32,
-    pub pos: Vec2,
+    pub pos: Pos,
 }
 
 impl Piece {
@@ -14,7 +13,7 @@ impl Piece {
         Self {
             tetromino,
             rot: 0,
-            pos: Vec2::zero(),
+            pos: Pos::zero(),
         }
     }
 
@@ -23,7 +22,15 @@ impl Piece {
         self
     }
 
-    pub fn cells(&self) -> Vec<Vec2> {
+    pub fn cells(&self) -> Vec<Pos> {
         self.tetromino.cells(self.rot)
     }
+
+    // cells in board coordinates
+    pub fn board_cells(&self) -> Vec<Pos> {
+        self.cells()
+            .into_iter()
+            .map(|cell| self.pos + cell)
+            .collect()
+    }
 }
@@ -0,0 +1,236 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::tetromino::Tetromino;
+
+// picks the upcoming pieces, the same seed always gives the same sequence
+pub trait Randomizer {
+    fn next_piece(&mut self) -> Tetromino;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RandomizerKind {
+    Uniform,
+    #[default]
+    Bag7,
+    Bag14,
+    Tgm,
+    Nes,
+}
+
+impl RandomizerKind {
+    pub const ALL: [RandomizerKind; 5] = [
+        RandomizerKind::Uniform,
+        RandomizerKind::Bag7,
+        RandomizerKind::Bag14,
+        RandomizerKind::Tgm,
+        RandomizerKind::Nes,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RandomizerKind::Uniform => "uniform",
+            RandomizerKind::Bag7 => "7-bag",
+            RandomizerKind::Bag14 => "14-bag",
+            RandomizerKind::Tgm => "tgm",
+            RandomizerKind::Nes => "nes",
+        }
+    }
+
+    pub fn build(&self, seed: u64) -> Box<dyn Randomizer> {
+        let rng = StdRng::seed_from_u64(seed);
+        match self {
+            RandomizerKind::Uniform => Box::new(UniformRandomizer::new(rng)),
+            RandomizerKind::Bag7 => Box::new(BagRandomizer::new(rng, 1)),
+            RandomizerKind::Bag14 => Box::new(BagRandomizer::new(rng, 2)),
+            RandomizerKind::Tgm => Box::new(TgmRandomizer::new(rng)),
+            RandomizerKind::Nes => Box::new(NesRandomizer::new(rng)),
+        }
+    }
+}
+
+impl fmt::Display for RandomizerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for RandomizerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RandomizerKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| format!("unknown randomizer: {s}"))
+    }
+}
+
+fn random_piece(rng: &mut StdRng) -> Tetromino {
+    Tetromino::ALL[rng.gen_range(0..Tetromino::ALL.len())]
+}
+
+//--------------------------------------------------------------------------------
+// uniform: every piece has the same odds, every time
+//--------------------------------------------------------------------------------
+
+pub struct UniformRandomizer {
+    rng: StdRng,
+}
+
+impl UniformRandomizer {
+    pub fn new(rng: StdRng) -> Self {
+        Self { rng }
+    }
+}
+
+impl Randomizer for UniformRandomizer {
+    fn next_piece(&mut self) -> Tetromino {
+        random_piece(&mut self.rng)
+    }
+}
+
+//--------------------------------------------------------------------------------
+// bag: deal a shuffled bag holding `copies` of each piece, then refill
+//--------------------------------------------------------------------------------
+
+pub struct BagRandomizer {
+    rng: StdRng,
+    copies: usize,
+    bag: Vec<Tetromino>,
+}
+
+impl BagRandomizer {
+    pub fn new(rng: StdRng, copies: usize) -> Self {
+        Self {
+            rng,
+            copies: copies.max(1),
+            bag: vec![],
+        }
+    }
+
+    fn refill(&mut self) {
+        for _ in 0..self.copies {
+            self.bag.extend(Tetromino::ALL);
+        }
+        self.bag.shuffle(&mut self.rng);
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next_piece(&mut self) -> Tetromino {
+        if self.bag.is_empty() {
+            self.refill();
+        }
+        self.bag.pop().unwrap()
+    }
+}
+
+//--------------------------------------------------------------------------------
+// tgm: reroll (up to a few times) anything found in the last 4 pieces
+//--------------------------------------------------------------------------------
+
+const TGM_ROLLS: usize = 6;
+
+pub struct TgmRandomizer {
+    rng: StdRng,
+    history: [Tetromino; 4],
+    first: bool,
+}
+
+impl TgmRandomizer {
+    pub fn new(rng: StdRng) -> Self {
+        Self {
+            rng,
+            history: [Tetromino::Z, Tetromino::S, Tetromino::S, Tetromino::Z],
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for TgmRandomizer {
+    fn next_piece(&mut self) -> Tetromino {
+        let piece = if self.first {
+            // never start with an S, Z or O
+            self.first = false;
+            *[Tetromino::I, Tetromino::J, Tetromino::L, Tetromino::T]
+                .choose(&mut self.rng)
+                .unwrap()
+        } else {
+            let mut piece = random_piece(&mut self.rng);
+            for _ in 1..TGM_ROLLS {
+                if !self.history.contains(&piece) {
+                    break;
+                }
+                piece = random_piece(&mut self.rng);
+            }
+            piece
+        };
+
+        self.history.rotate_left(1);
+        self.history[3] = piece;
+        piece
+    }
+}
+
+//--------------------------------------------------------------------------------
+// nes: roll a d8, reroll once (with a d7) on a repeat or on the dummy 8th side
+//--------------------------------------------------------------------------------
+
+pub struct NesRandomizer {
+    rng: StdRng,
+    prev: Option<Tetromino>,
+}
+
+impl NesRandomizer {
+    pub fn new(rng: StdRng) -> Self {
+        Self { rng, prev: None }
+    }
+}
+
+impl Randomizer for NesRandomizer {
+    fn next_piece(&mut self) -> Tetromino {
+        let roll = self.rng.gen_range(0..=Tetromino::ALL.len());
+        let piece = match Tetromino::ALL.get(roll) {
+            Some(piece) if Some(*piece) != self.prev => *piece,
+            _ => random_piece(&mut self.rng),
+        };
+        self.prev = Some(piece);
+        piece
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deal(kind: RandomizerKind, seed: u64, count: usize) -> Vec<Tetromino> {
+        let mut randomizer = kind.build(seed);
+        (0..count).map(|_| randomizer.next_piece()).collect()
+    }
+
+    #[test]
+    fn same_seed_same_sequence() {
+        for kind in RandomizerKind::ALL {
+            assert_eq!(deal(kind, 42, 100), deal(kind, 42, 100), "{kind}");
+            assert_ne!(deal(kind, 42, 100), deal(kind, 43, 100), "{kind}");
+        }
+    }
+
+    #[test]
+    fn bags_hold_every_piece() {
+        for (kind, copies) in [(RandomizerKind::Bag7, 1), (RandomizerKind::Bag14, 2)] {
+            let size = 7 * copies;
+            for bag in deal(kind, 7, size * 20).chunks(size) {
+                for tetromino in Tetromino::ALL {
+                    let count = bag.iter().filter(|piece| **piece == tetromino).count();
+                    assert_eq!(count, copies, "{kind}: {bag:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const RECORDS_FILE: &str = ".tetris_records";
+
+// personal bests, kept in a small `key value` text file in the home directory
+#[derive(Debug, Default)]
+pub struct Records {
+    path: Option<PathBuf>,
+    bests: BTreeMap<String, u64>,
+}
+
+impl Records {
+    // a missing or unreadable file just means no records yet
+    pub fn load() -> Self {
+        let path = env::var_os("HOME").map(|home| PathBuf::from(home).join(RECORDS_FILE));
+        let content = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        let bests = content
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once(' ')?;
+                Some((key.to_string(), value.trim().parse().ok()?))
+            })
+            .collect();
+        Self { path, bests }
+    }
+
+    pub fn best(&self, key: &str) -> Option<u64> {
+        self.bests.get(key).copied()
+    }
+
+    // keeps (and saves) the value if it beats the previous best, returns whether it did
+    pub fn submit(&mut self, key: &str, value: u64, lower_is_better: bool) -> bool {
+        let is_best = match self.best(key) {
+            None => true,
+            Some(best) if lower_is_better => value < best,
+            Some(best) => value > best,
+        };
+        if is_best {
+            self.bests.insert(key.to_string(), value);
+            // not being able to save shouldn't get in the way of playing
+            let _ = self.save();
+        }
+        is_best
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let content: String = self
+            .bests
+            .iter()
+            .map(|(key, value)| format!("{key} {value}\n"))
+            .collect();
+        fs::write(path, content)
+    }
+}
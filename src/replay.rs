@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use crate::gamestate::{GameState, FRAME};
+use crate::handling::Handling;
+use crate::input::Input;
+use crate::rules::Rules;
+
+// an input, and the frame it came in on (before that frame was played)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayInput {
+    pub frame: u64,
+    pub input: Input,
+    pub pressed: bool,
+}
+
+// the engine is deterministic, so this is all it takes to play a game again
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: Rules,
+    pub handling: Handling,
+    pub inputs: Vec<ReplayInput>,
+    // the frame the player went on past the goal at, if they did
+    pub continued_at: Option<u64>,
+}
+
+// plays a replay back in real time, feeding its inputs to a fresh game
+pub struct Playback {
+    state: GameState,
+    inputs: Vec<ReplayInput>,
+    next_input: usize,
+    continued_at: Option<u64>,
+    time_acc: Duration,
+}
+
+impl Playback {
+    pub fn new(replay: &Replay) -> Self {
+        let mut state = GameState::with_rules(replay.seed, replay.rules.clone());
+        state.set_handling(replay.handling);
+        state.init();
+        Self {
+            state,
+            inputs: replay.inputs.clone(),
+            next_input: 0,
+            continued_at: replay.continued_at,
+            time_acc: Duration::ZERO,
+        }
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut GameState {
+        &mut self.state
+    }
+
+    // the game is over and wasn't played past that point
+    pub fn is_finished(&self) -> bool {
+        self.state.is_game_over() && !self.continues_now()
+    }
+
+    fn continues_now(&self) -> bool {
+        self.state.can_keep_going() && self.continued_at == Some(self.state.frame())
+    }
+
+    pub fn update(&mut self, dt: Duration) {
+        self.time_acc += dt;
+        while self.time_acc >= FRAME && !self.is_finished() {
+            self.time_acc -= FRAME;
+            self.tick();
+        }
+    }
+
+    // feed the inputs that came in before this frame, then play it
+    pub fn tick(&mut self) {
+        if self.continues_now() {
+            self.state.keep_going();
+        }
+        while let Some(input) = self.inputs.get(self.next_input) {
+            if input.frame > self.state.frame() {
+                break;
+            }
+            if input.pressed {
+                self.state.press(input.input);
+            } else {
+                self.state.release(input.input);
+            }
+            self.next_input += 1;
+        }
+        self.state.tick();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playback_matches_the_recorded_game() {
+        let mut state = GameState::new(1234);
+        state.init();
+        let inputs = [
+            Input::MoveLeft,
+            Input::RotateCw,
+            Input::HardDrop,
+            Input::MoveRight,
+            Input::Hold,
+            Input::Rotate180,
+            Input::SoftDrop,
+            Input::RotateCcw,
+            Input::HardDrop,
+        ];
+        for frame in 0..2000 {
+            // press something every few frames, and let go of it a bit later
+            let input = inputs[frame / 7 % inputs.len()];
+            match frame % 7 {
+                0 => {
+                    state.press(input);
+                }
+                3 => state.release(input),
+                _ => (),
+            }
+            state.tick();
+        }
+
+        let mut playback = Playback::new(&state.replay());
+        while playback.state().frame() < state.frame() {
+            playback.tick();
+        }
+        // the input that ended the game came in after its last frame, it's fed on the next one
+        if state.is_game_over() {
+            playback.tick();
+        }
+        let replayed = playback.state();
+        assert_eq!(replayed.end_reason(), state.end_reason());
+        assert_eq!(replayed.score(), state.score());
+        assert_eq!(replayed.lines_cleared(), state.lines_cleared());
+        assert_eq!(replayed.stats().pieces, state.stats().pieces);
+        assert_eq!(replayed.current_piece(), state.current_piece());
+        assert!(replayed.board().rows().eq(state.board().rows()));
+    }
+}
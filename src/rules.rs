@@ -0,0 +1,135 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::board::{DEFAULT_BUFFER, DEFAULT_HEIGHT, DEFAULT_WIDTH};
+use crate::randomizer::RandomizerKind;
+use crate::scoring::ScoringTable;
+
+// how fast pieces fall, in cells per frame (at 60 fps)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gravity {
+    // follows the level, up to level 10
+    Classic,
+    // follows the level along the guideline curve, 20G from level 20 on
+    Guideline,
+    // same speed whatever the level, e.g. 1.0 / 60.0 (a cell per second) or 20.0 (20G)
+    Fixed(f64),
+}
+
+pub const GRAVITY_20G: f64 = 20.0;
+
+// what gives a grounded piece a fresh lock delay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockReset {
+    // any move or rotation, at most this many times per piece (guideline: 15)
+    Move(u32),
+    // any move or rotation, no limit
+    Infinity,
+    // only moving down a row
+    Step,
+}
+
+// move:<n>, infinity or step
+impl FromStr for LockReset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "infinity" => Ok(LockReset::Infinity),
+            "step" => Ok(LockReset::Step),
+            _ => {
+                let resets = s
+                    .strip_prefix("move:")
+                    .ok_or_else(|| format!("unknown lock reset: {s}"))?;
+                resets
+                    .parse()
+                    .map(LockReset::Move)
+                    .map_err(|_| format!("invalid number of lock resets: {resets}"))
+            }
+        }
+    }
+}
+
+// how the level goes up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelGoal {
+    // one level per this many points, from line clears and spins only
+    Points(i32),
+    // one level per this many lines (guideline fixed goal: 10)
+    Lines(i32),
+    // guideline variable goal: leaving level n takes 5 * n more lines, as awarded per clear
+    // (a single is 1, a tetris 8, a T-spin double 12...)
+    Variable,
+}
+
+// what ends the game on its own, besides topping out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    // clear this many lines (sprint)
+    Lines(i32),
+    // play for this long (ultra)
+    Time(Duration),
+    // dig through all the garbage rows (cheese)
+    Garbage,
+    // get through this level, whatever the level goal (marathon)
+    Level(i32),
+}
+
+// everything that can change from one game mode to another
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    // in cells, at least 4x4
+    pub board_width: i32,
+    pub board_height: i32,
+    // hidden rows above the visible playfield, 0 for classic modes
+    pub buffer_rows: i32,
+    pub randomizer: RandomizerKind,
+    // classic modes have no hold slot
+    pub hold_enabled: bool,
+    pub gravity: Gravity,
+    pub level_goal: LevelGoal,
+    // the level stops going up here
+    pub max_level: i32,
+    // how long a piece can stay on the ground before it locks
+    pub lock_delay: Duration,
+    pub lock_reset: LockReset,
+    // time between a piece locking and the next one spawning (ARE)
+    pub entry_delay: Duration,
+    // extra time the full rows stay on the board before being removed
+    pub line_clear_delay: Duration,
+    pub scoring: ScoringTable,
+    // S, Z, J, L and I pieces stuck in place by a rotation score as mini spins
+    pub all_spin: bool,
+    // None plays until the player tops out
+    pub goal: Option<Goal>,
+    // garbage to dig through, fed from the bottom with at most garbage_height rows on the board
+    pub garbage_rows: i32,
+    pub garbage_height: i32,
+    // odds of the hole moving from one garbage row to the next, 0 gives a straight well
+    pub garbage_messiness: f64,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            board_width: DEFAULT_WIDTH,
+            board_height: DEFAULT_HEIGHT,
+            buffer_rows: DEFAULT_BUFFER,
+            randomizer: RandomizerKind::default(),
+            hold_enabled: true,
+            gravity: Gravity::Classic,
+            level_goal: LevelGoal::Lines(10),
+            max_level: 10,
+            lock_delay: Duration::from_millis(500),
+            lock_reset: LockReset::Move(15),
+            entry_delay: Duration::ZERO,
+            line_clear_delay: Duration::ZERO,
+            scoring: ScoringTable::guideline(),
+            all_spin: false,
+            goal: None,
+            garbage_rows: 0,
+            garbage_height: 10,
+            garbage_messiness: 1.0,
+        }
+    }
+}
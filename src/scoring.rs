@@ -0,0 +1,242 @@
+use std::str::FromStr;
+
+use crate::spin::Spin;
+
+// a line clear (or a spin that cleared nothing), as seen by the scoring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineClear {
+    pub lines: i32,
+    pub spin: Spin,
+    // previous line clear was also a difficult one
+    pub back_to_back: bool,
+    // number of consecutive pieces that cleared lines before this one
+    pub combo: i32,
+    // the board is empty afterwards
+    pub perfect_clear: bool,
+}
+
+// lines awarded towards the guideline variable goal, whatever the scoring table
+const GOAL_LINES: [i32; 5] = [0, 1, 3, 5, 8];
+const GOAL_SPINS: [i32; 4] = [4, 8, 12, 16];
+const GOAL_MINI_SPINS: [i32; 3] = [1, 2, 4];
+
+impl LineClear {
+    // the clears that keep a back to back chain going
+    pub fn is_difficult(&self) -> bool {
+        self.lines >= 4 || (self.lines > 0 && self.spin != Spin::None)
+    }
+
+    // back to back clears are worth half as much again
+    pub fn goal_lines(&self) -> i32 {
+        let lines = self.lines.max(0) as usize;
+        let awarded = match self.spin {
+            Spin::None => GOAL_LINES.get(lines),
+            Spin::Mini => GOAL_MINI_SPINS.get(lines),
+            Spin::Full => GOAL_SPINS.get(lines),
+        };
+        let awarded = awarded.copied().unwrap_or_default();
+        if self.back_to_back && self.is_difficult() {
+            awarded + awarded / 2
+        } else {
+            awarded
+        }
+    }
+}
+
+// how many points each action is worth, swappable per mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoringTable {
+    // indexed by the number of lines cleared, multiplied by the level
+    pub lines: [i32; 5],
+    // same for T-spins and mini T-spins
+    pub spins: [i32; 4],
+    pub mini_spins: [i32; 3],
+    // bonus on top of the line clear when it empties the board, multiplied by the level
+    pub perfect_clears: [i32; 5],
+    pub back_to_back_perfect_clear: i32,
+    // difficult clears in a row are worth this percentage of their points
+    pub back_to_back: i32,
+    // per combo step, multiplied by the level
+    pub combo: i32,
+    // per row dropped
+    pub soft_drop: i32,
+    pub hard_drop: i32,
+}
+
+impl Default for ScoringTable {
+    fn default() -> Self {
+        Self::guideline()
+    }
+}
+
+impl ScoringTable {
+    // the built in tables, by name
+    pub const NAMES: [&'static str; 2] = ["guideline", "classic"];
+
+    pub fn name(&self) -> &'static str {
+        if *self == Self::guideline() {
+            "guideline"
+        } else if *self == Self::classic() {
+            "classic"
+        } else {
+            "custom"
+        }
+    }
+
+    pub fn guideline() -> Self {
+        Self {
+            lines: [0, 100, 300, 500, 800],
+            spins: [400, 800, 1200, 1600],
+            mini_spins: [100, 200, 400],
+            perfect_clears: [0, 800, 1200, 1800, 2000],
+            back_to_back_perfect_clear: 3200,
+            back_to_back: 150,
+            combo: 50,
+            soft_drop: 1,
+            hard_drop: 2,
+        }
+    }
+
+    // nes (our level 1 is its level 0): no back to back, no combos, no hard drop, no perfect clear bonus, spins are just line clears
+    pub fn classic() -> Self {
+        Self {
+            lines: [0, 40, 100, 300, 1200],
+            spins: [0, 40, 100, 300],
+            mini_spins: [0, 40, 100],
+            perfect_clears: [0; 5],
+            back_to_back_perfect_clear: 0,
+            back_to_back: 100,
+            combo: 0,
+            soft_drop: 1,
+            hard_drop: 0,
+        }
+    }
+
+    pub fn line_clear(&self, clear: &LineClear, level: i32) -> i32 {
+        let lines = clear.lines.max(0) as usize;
+        let points = match clear.spin {
+            Spin::None => self.lines.get(lines),
+            Spin::Mini => self.mini_spins.get(lines),
+            Spin::Full => self.spins.get(lines),
+        };
+
+        let mut points = points.copied().unwrap_or_default() * level;
+        if clear.back_to_back && clear.is_difficult() {
+            points = points * self.back_to_back / 100;
+        }
+        if clear.perfect_clear {
+            let bonus = if clear.back_to_back && clear.lines >= 4 {
+                self.back_to_back_perfect_clear
+            } else {
+                self.perfect_clears[lines.min(4)]
+            };
+            points += bonus * level;
+        }
+        points + self.combo * clear.combo * level
+    }
+}
+
+impl FromStr for ScoringTable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "guideline" => Ok(Self::guideline()),
+            "classic" => Ok(Self::classic()),
+            _ => Err(format!("unknown scoring: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clear(lines: i32, spin: Spin) -> LineClear {
+        LineClear {
+            lines,
+            spin,
+            back_to_back: false,
+            combo: 0,
+            perfect_clear: false,
+        }
+    }
+
+    #[test]
+    fn guideline_line_clears() {
+        let table = ScoringTable::guideline();
+        assert_eq!(table.line_clear(&clear(1, Spin::None), 1), 100);
+        assert_eq!(table.line_clear(&clear(4, Spin::None), 2), 1600);
+        assert_eq!(table.line_clear(&clear(2, Spin::Full), 1), 1200);
+        assert_eq!(table.line_clear(&clear(0, Spin::Full), 1), 400);
+        assert_eq!(table.line_clear(&clear(1, Spin::Mini), 1), 200);
+    }
+
+    #[test]
+    fn guideline_back_to_back() {
+        let table = ScoringTable::guideline();
+        let tetris = LineClear {
+            back_to_back: true,
+            ..clear(4, Spin::None)
+        };
+        assert_eq!(table.line_clear(&tetris, 1), 1200);
+        let t_spin_double = LineClear {
+            back_to_back: true,
+            ..clear(2, Spin::Full)
+        };
+        assert_eq!(table.line_clear(&t_spin_double, 1), 1800);
+        // singles don't keep the chain going, so they get no bonus
+        let single = LineClear {
+            back_to_back: true,
+            ..clear(1, Spin::None)
+        };
+        assert_eq!(table.line_clear(&single, 1), 100);
+    }
+
+    #[test]
+    fn guideline_combo() {
+        let table = ScoringTable::guideline();
+        let single = LineClear {
+            combo: 3,
+            ..clear(1, Spin::None)
+        };
+        assert_eq!(table.line_clear(&single, 1), 250);
+        assert_eq!(table.line_clear(&single, 2), 500);
+    }
+
+    #[test]
+    fn guideline_perfect_clear() {
+        let table = ScoringTable::guideline();
+        let single = LineClear {
+            perfect_clear: true,
+            ..clear(1, Spin::None)
+        };
+        assert_eq!(table.line_clear(&single, 1), 900);
+        let tetris = LineClear {
+            perfect_clear: true,
+            back_to_back: true,
+            ..clear(4, Spin::None)
+        };
+        assert_eq!(table.line_clear(&tetris, 1), 1200 + 3200);
+    }
+
+    #[test]
+    fn classic_starts_at_nes_level_0() {
+        let table = ScoringTable::classic();
+        assert_eq!(table.line_clear(&clear(1, Spin::None), 1), 40);
+        assert_eq!(table.line_clear(&clear(4, Spin::None), 1), 1200);
+        assert_eq!("classic".parse(), Ok(table));
+    }
+
+    #[test]
+    fn variable_goal_lines() {
+        assert_eq!(clear(1, Spin::None).goal_lines(), 1);
+        assert_eq!(clear(4, Spin::None).goal_lines(), 8);
+        assert_eq!(clear(2, Spin::Full).goal_lines(), 12);
+        let tetris = LineClear {
+            back_to_back: true,
+            ..clear(4, Spin::None)
+        };
+        assert_eq!(tetris.goal_lines(), 12);
+    }
+}
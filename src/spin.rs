@@ -0,0 +1,160 @@
+use crate::board::Board;
+use crate::piece::Piece;
+use crate::pos::Pos;
+use crate::tetromino::Tetromino;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Spin {
+    #[default]
+    None,
+    Mini,
+    Full,
+}
+
+// T pieces always use the 3-corner rule, with all_spin the other pieces (but the O)
+// also count as (mini) spins when they got stuck by rotating
+// rotation is the last move of the piece if it was a rotation: quarter turns and kick index
+pub fn detect_spin(
+    board: &Board,
+    piece: &Piece,
+    rotation: Option<(i32, usize)>,
+    all_spin: bool,
+) -> Spin {
+    match piece.tetromino {
+        Tetromino::T => detect_t_spin(board, piece, rotation),
+        Tetromino::O => Spin::None,
+        _ if all_spin && rotation.is_some() && is_immobile(board, piece) => Spin::Mini,
+        _ => Spin::None,
+    }
+}
+
+// the SRS kick that lets a T go through a 1 wide gap two rows down, always a full T-spin.
+// only for quarter turns, the same index in the 180 table is a plain sideways shift
+const TST_KICK: usize = 4;
+
+// 3-corner rule: the piece got there by rotating (rotation is Some) and 3 of the 4
+// cells diagonal to the center of the T are taken. it's a full T-spin when both
+// corners in front of the T (the side it points to) are taken, a mini otherwise.
+pub fn detect_t_spin(board: &Board, piece: &Piece, rotation: Option<(i32, usize)>) -> Spin {
+    let Some((delta, kick)) = rotation else {
+        return Spin::None;
+    };
+    if piece.tetromino != Tetromino::T {
+        return Spin::None;
+    }
+
+    // corners around the center (1, 1), the first two being in front of the T
+    let corners = match piece.rot.rem_euclid(4) {
+        0 => [(0, 0), (2, 0), (0, 2), (2, 2)],
+        1 => [(2, 0), (2, 2), (0, 0), (0, 2)],
+        2 => [(0, 2), (2, 2), (0, 0), (2, 0)],
+        _ => [(0, 0), (0, 2), (2, 0), (2, 2)],
+    };
+    let taken = corners.map(|(x, y)| is_taken(board, piece.pos + Pos::xy(x, y)));
+
+    if taken.iter().filter(|taken| **taken).count() < 3 {
+        Spin::None
+    } else if (taken[0] && taken[1]) || (delta.rem_euclid(2) == 1 && kick == TST_KICK) {
+        Spin::Full
+    } else {
+        Spin::Mini
+    }
+}
+
+// can't move left, right or up (down is a given, since it's locking)
+pub fn is_immobile(board: &Board, piece: &Piece) -> bool {
+    [Pos::xy(-1, 0), Pos::xy(1, 0), Pos::xy(0, -1)]
+        .into_iter()
+        .all(|delta| {
+            let mut moved = *piece;
+            moved.pos += delta;
+            !board.fits(&moved)
+        })
+}
+
+// walls and floor count as taken, the space above the board doesn't
+fn is_taken(board: &Board, pos: Pos) -> bool {
+    if pos.y < -board.buffer() && (0..board.width()).contains(&pos.x) {
+        return false;
+    }
+    !board.is_in_grid(pos) || !board.is_in_empty_pos(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::kicks::kicks;
+
+    // 6x6, all filled but a T sitting on top of a TST slot. the slot's far front
+    // corner is open too, so only the TST kick makes it a full T-spin
+    fn tst_board() -> Board {
+        let open = [
+            (3, 1),
+            (2, 2),
+            (3, 2),
+            (4, 2),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+            (2, 5),
+            (3, 5),
+        ];
+        let mut board = Board::new(6, 6, 0);
+        for y in 0..6 {
+            for x in 0..6 {
+                if !open.contains(&(x, y)) {
+                    board.set_cell(Pos::xy(x, y), Cell::Garbage);
+                }
+            }
+        }
+        board
+    }
+
+    // the piece after rotating, and the index of the kick that got it there
+    fn rotate(board: &Board, piece: Piece, delta: i32) -> Option<(Piece, usize)> {
+        let mut rotated = piece;
+        rotated.rotate(delta);
+        kicks(piece.tetromino, piece.rot, delta)
+            .into_iter()
+            .enumerate()
+            .find_map(|(kick, offset)| {
+                let mut kicked = rotated;
+                kicked.pos += offset;
+                board.fits(&kicked).then_some((kicked, kick))
+            })
+    }
+
+    #[test]
+    fn tst_kick_is_a_full_t_spin() {
+        let board = tst_board();
+        let piece = Piece {
+            tetromino: Tetromino::T,
+            rot: 0,
+            pos: Pos::xy(2, 1),
+        };
+        let (piece, kick) = rotate(&board, piece, 1).unwrap();
+        assert_eq!(kick, TST_KICK);
+        assert_eq!(piece.pos, Pos::xy(1, 3));
+        assert_eq!(
+            detect_spin(&board, &piece, Some((1, kick)), false),
+            Spin::Full
+        );
+    }
+
+    #[test]
+    fn same_kick_index_on_a_180_is_a_mini() {
+        let board = tst_board();
+        let piece = Piece {
+            tetromino: Tetromino::T,
+            rot: 1,
+            pos: Pos::xy(1, 3),
+        };
+        assert_eq!(
+            detect_t_spin(&board, &piece, Some((2, TST_KICK))),
+            Spin::Mini
+        );
+        assert_eq!(detect_t_spin(&board, &piece, Some((1, 0))), Spin::Mini);
+        assert_eq!(detect_t_spin(&board, &piece, None), Spin::None);
+    }
+}
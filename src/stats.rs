@@ -0,0 +1,33 @@
+use crate::scoring::LineClear;
+use crate::spin::Spin;
+
+// running totals for the current game
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub pieces: u32,
+    // indexed by the number of lines cleared at once
+    pub clears: [u32; 5],
+    pub spins: u32,
+    pub mini_spins: u32,
+    pub back_to_backs: u32,
+    pub perfect_clears: u32,
+    pub max_combo: i32,
+}
+
+impl Stats {
+    pub fn add_clear(&mut self, clear: &LineClear) {
+        self.clears[clear.lines.clamp(0, 4) as usize] += 1;
+        match clear.spin {
+            Spin::None => (),
+            Spin::Mini => self.mini_spins += 1,
+            Spin::Full => self.spins += 1,
+        }
+        if clear.back_to_back && clear.is_difficult() {
+            self.back_to_backs += 1;
+        }
+        if clear.perfect_clear {
+            self.perfect_clears += 1;
+        }
+        self.max_combo = self.max_combo.max(clear.combo);
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::Instant;
+
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::{Key, KeyEvent};
+use tetris::{GameState, Playback, Replay};
+
+use super::gameview::{Action, GameView};
+use super::mode::Mode;
+use super::replays::SavedReplay;
+use super::results::ResultsView;
+use super::screen::{Context, Screen, Transition};
+
+enum Play {
+    Live(GameState),
+    Replay(Playback),
+}
+
+// a game being played, or a replay being watched
+pub struct GameScreen {
+    mode: Mode,
+    play: Play,
+    view: GameView,
+    last_frame: Instant,
+}
+
+impl GameScreen {
+    // a fresh game from the current settings
+    pub fn new(ctx: &Context, mode: Mode) -> Self {
+        let settings = &ctx.settings;
+        let seed = settings.seed.unwrap_or_else(rand::random);
+        let mut rules = settings.rules.clone();
+        mode.apply(&mut rules);
+        let mut state = GameState::with_rules(seed, rules);
+        state.set_handling(settings.handling);
+        let mut view = GameView::new(ctx.win_size, state.board());
+        view.set_clear_animation(settings.clear_animation);
+        state.init();
+        Self {
+            mode,
+            play: Play::Live(state),
+            view,
+            last_frame: Instant::now(),
+        }
+    }
+
+    pub fn replay(ctx: &Context, mode: Mode, replay: &Replay) -> Self {
+        let playback = Playback::new(replay);
+        let mut view = GameView::new(ctx.win_size, playback.state().board());
+        view.set_clear_animation(ctx.settings.clear_animation);
+        view.set_watching(true);
+        Self {
+            mode,
+            play: Play::Replay(playback),
+            view,
+            last_frame: Instant::now(),
+        }
+    }
+
+    fn state(&self) -> &GameState {
+        match &self.play {
+            Play::Live(state) => state,
+            Play::Replay(playback) => playback.state(),
+        }
+    }
+
+    pub fn handle_key_events(
+        &mut self,
+        ctx: &mut Context,
+        key_events: &[KeyEvent],
+    ) -> Option<Transition> {
+        let state = match &mut self.play {
+            Play::Live(state) => state,
+            // watching only, any way out goes back
+            Play::Replay(_) => {
+                let leave = key_events
+                    .iter()
+                    .any(|key_event| matches!(key_event, KeyEvent::Pressed(Key::Esc | Key::Enter)));
+                return leave.then_some(Transition::Pop);
+            }
+        };
+
+        match self.view.handle_key_events(state, key_events)? {
+            Action::ShowResults => {
+                ctx.save_replay(SavedReplay::new(state, self.mode));
+                let ranked = ctx.settings.is_ranked();
+                let results = ResultsView::new(state, self.mode, ranked, &mut ctx.records);
+                Some(Transition::Replace(Screen::Results(results)))
+            }
+            Action::Restart => Some(Transition::Replace(Screen::Game(Box::new(
+                GameScreen::new(ctx, self.mode),
+            )))),
+            Action::Quit => Some(Transition::Pop),
+        }
+    }
+
+    pub fn update(&mut self, step: usize) {
+        let now = Instant::now();
+        let dt = now - self.last_frame;
+        self.last_frame = now;
+
+        let state = match &mut self.play {
+            Play::Live(state) => {
+                state.update(dt);
+                state
+            }
+            Play::Replay(playback) => {
+                playback.update(dt);
+                playback.state_mut()
+            }
+        };
+        for event in state.take_events() {
+            self.view.handle_event(state, &event);
+        }
+        self.view.update(state, step);
+    }
+
+    pub fn draw(&self, pencil: &mut Pencil) {
+        self.view.draw(self.state(), pencil);
+    }
+}
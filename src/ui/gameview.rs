@@ -0,0 +1,580 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::{Key, KeyEvent};
+use ruscii::spatial::Vec2;
+use ruscii::terminal::Color;
+use tetris::{Board, Cell, EndReason, Event, GameState, Goal, Input, Piece, Pos};
+
+use super::{cell_color, format_time, tetromino_color};
+
+const GAMEOVER_WIDTH: i32 = 11; // real size
+const GAMEOVER_HEIGHT: i32 = 3;
+
+const SPAWN_ROWS_SHOWN: i32 = 2;
+
+const BANNER_TIME: Duration = Duration::from_secs(2);
+// the countdown of timed games turns red
+const HURRY_TIME: Duration = Duration::from_secs(10);
+// so that a hard drop mashed while topping out doesn't skip the game over
+const GAMEOVER_KEY_DELAY: Duration = Duration::from_secs(1);
+
+// line goals show the time taken for every this many lines, the last few of them
+const SPLIT_LINES: i32 = 10;
+const SPLITS_SHOWN: usize = 5;
+
+const PAUSE_MENU: [&str; 3] = ["resume", "restart", "menu"];
+const PAUSE_MENU_WIDTH: i32 = 13;
+
+// what the player asked for that goes beyond the current game, left to main
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    ShowResults,
+    Restart,
+    Quit,
+}
+
+// how full rows disappear during the line clear delay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClearAnimation {
+    None,
+    Flash,
+    #[default]
+    Wipe,
+    Fade,
+}
+
+impl ClearAnimation {
+    pub const ALL: [ClearAnimation; 4] = [
+        ClearAnimation::None,
+        ClearAnimation::Flash,
+        ClearAnimation::Wipe,
+        ClearAnimation::Fade,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClearAnimation::None => "none",
+            ClearAnimation::Flash => "flash",
+            ClearAnimation::Wipe => "wipe",
+            ClearAnimation::Fade => "fade",
+        }
+    }
+
+    // color of a cell in a row being cleared, None once it's gone.
+    // progress goes from 0 to 1 over the line clear delay
+    fn color(&self, cell: Cell, x: i32, width: i32, progress: f64) -> Option<Color> {
+        let color = cell_color(cell)?;
+        match self {
+            ClearAnimation::None => Some(color),
+            // blink 3 times
+            ClearAnimation::Flash => {
+                if (progress * 6.0) as i32 % 2 == 0 {
+                    Some(Color::White)
+                } else {
+                    Some(color)
+                }
+            }
+            // from the center outward
+            ClearAnimation::Wipe => {
+                let center = (width - 1) as f64 / 2.0;
+                let reach = progress * (center + 1.0);
+                if (x as f64 - center).abs() < reach {
+                    None
+                } else {
+                    Some(color)
+                }
+            }
+            // through the grey ramp, from white (255) to almost black (232)
+            ClearAnimation::Fade => Some(Color::Xterm(255 - (progress * 23.0) as u8)),
+        }
+    }
+}
+
+impl FromStr for ClearAnimation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ClearAnimation::ALL
+            .into_iter()
+            .find(|animation| animation.name() == s)
+            .ok_or_else(|| format!("unknown clear animation: {s}"))
+    }
+}
+
+fn key_to_input(key: Key) -> Option<Input> {
+    match key {
+        Key::Up | Key::X => Some(Input::RotateCw),
+        Key::Z => Some(Input::RotateCcw),
+        Key::A => Some(Input::Rotate180),
+        Key::C => Some(Input::Hold),
+        Key::Down => Some(Input::SoftDrop),
+        Key::Space | Key::Enter => Some(Input::HardDrop),
+        Key::Left => Some(Input::MoveLeft),
+        Key::Right => Some(Input::MoveRight),
+        _ => None,
+    }
+}
+
+// terminal frontend for a GameState: maps keys to inputs and draws the board
+pub struct GameView {
+    grid_pos: Vec2,
+    // gameover
+    gameover_pos: Vec2,
+    gameover_speed: Vec2,
+    gameover_at: Option<Instant>,
+    // shown over the board for a little while after something special happened
+    banner: Option<(&'static str, Instant)>,
+    clear_animation: ClearAnimation,
+    // selected entry of the pause menu
+    pause_selection: usize,
+    // playing back a replay, not a game
+    watching: bool,
+    // game time at every SPLIT_LINES lines
+    splits: Vec<Duration>,
+}
+
+impl GameView {
+    pub fn new(dim: Vec2, board: &Board) -> Self {
+        Self {
+            // each grid unit == 2 dots
+            grid_pos: Vec2::xy(
+                (dim.x - board.width() * 2) / 2,
+                (dim.y - board.height()) / 2,
+            ),
+            gameover_pos: Vec2::zero(),
+            gameover_speed: Vec2::zero(),
+            gameover_at: None,
+            banner: None,
+            clear_animation: ClearAnimation::default(),
+            pause_selection: 0,
+            watching: false,
+            splits: vec![],
+        }
+    }
+
+    pub fn set_watching(&mut self, watching: bool) {
+        self.watching = watching;
+    }
+
+    pub fn set_clear_animation(&mut self, clear_animation: ClearAnimation) {
+        self.clear_animation = clear_animation;
+    }
+
+    // repeats are left to the engine (das/arr), we only forward presses and releases
+    pub fn handle_key_events(
+        &mut self,
+        state: &mut GameState,
+        key_events: &[KeyEvent],
+    ) -> Option<Action> {
+        for key_event in key_events {
+            if state.is_paused() {
+                if let KeyEvent::Pressed(key) = *key_event {
+                    if let Some(action) = self.handle_pause_menu_key(state, key) {
+                        return Some(action);
+                    }
+                }
+                continue;
+            }
+
+            match *key_event {
+                KeyEvent::Pressed(Key::Esc) if state.is_game_over() => {
+                    return Some(Action::Quit);
+                }
+                KeyEvent::Pressed(Key::Enter | Key::R) if self.can_leave_gameover() => {
+                    return Some(Action::ShowResults);
+                }
+                KeyEvent::Pressed(Key::C) if self.can_leave_gameover() && state.keep_going() => {
+                    self.gameover_at = None;
+                }
+                KeyEvent::Pressed(Key::Esc | Key::P) => {
+                    state.pause();
+                    self.pause_selection = 0;
+                }
+                KeyEvent::Pressed(key) => {
+                    if let Some(input) = key_to_input(key) {
+                        state.press(input);
+                    }
+                }
+                KeyEvent::Released(key) => {
+                    if let Some(input) = key_to_input(key) {
+                        state.release(input);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn can_leave_gameover(&self) -> bool {
+        self.gameover_at
+            .is_some_and(|gameover_at| gameover_at.elapsed() > GAMEOVER_KEY_DELAY)
+    }
+
+    fn handle_pause_menu_key(&mut self, state: &mut GameState, key: Key) -> Option<Action> {
+        match key {
+            Key::Esc | Key::P => state.resume(),
+            Key::Up => {
+                self.pause_selection =
+                    (self.pause_selection + PAUSE_MENU.len() - 1) % PAUSE_MENU.len()
+            }
+            Key::Down => self.pause_selection = (self.pause_selection + 1) % PAUSE_MENU.len(),
+            Key::Enter | Key::Space => match self.pause_selection {
+                0 => state.resume(),
+                1 => return Some(Action::Restart),
+                _ => return Some(Action::Quit),
+            },
+            _ => (),
+        }
+        None
+    }
+
+    pub fn handle_event(&mut self, state: &GameState, event: &Event) {
+        match event {
+            Event::PerfectClear => self.banner = Some(("PERFECT CLEAR", Instant::now())),
+            // a few frames late at most, events are read once per update
+            Event::LinesCleared { .. } => {
+                while (self.splits.len() as i32 + 1) * SPLIT_LINES <= state.lines_cleared() {
+                    self.splits.push(state.elapsed());
+                }
+            }
+            Event::GameOver(_) => {
+                let width = state.board().width();
+                self.gameover_pos = Vec2::xy((width * 2 - GAMEOVER_WIDTH) / 2, 2);
+                self.gameover_speed = Vec2::xy(1, 1);
+                self.gameover_at = Some(Instant::now());
+            }
+            _ => (),
+        }
+    }
+
+    pub fn update(&mut self, state: &GameState, step: usize) {
+        if self
+            .banner
+            .is_some_and(|(_, shown_at)| shown_at.elapsed() > BANNER_TIME)
+        {
+            self.banner = None;
+        }
+
+        if state.is_game_over() && step.is_multiple_of(4) {
+            let board = state.board();
+            self.gameover_pos += self.gameover_speed;
+            // bounce off the borders (works even if the board is narrower than the box)
+            if self.gameover_pos.x + GAMEOVER_WIDTH >= board.width() * 2 {
+                self.gameover_speed.x = -1;
+            }
+            if self.gameover_pos.x <= 0 {
+                self.gameover_speed.x = 1;
+            }
+            if self.gameover_pos.y + GAMEOVER_HEIGHT >= board.height() {
+                self.gameover_speed.y = -1;
+            }
+            if self.gameover_pos.y <= 0 {
+                self.gameover_speed.y = 1;
+            }
+        }
+    }
+
+    pub fn draw(&self, state: &GameState, pencil: &mut Pencil) {
+        self.draw_running(state, pencil);
+        if state.is_paused() {
+            self.draw_pause_menu(state, pencil);
+            return;
+        }
+        if let Some((text, _)) = self.banner {
+            self.draw_banner(state, pencil, text);
+        }
+        if state.is_game_over() {
+            self.draw_gameover(state, pencil);
+            if self.can_leave_gameover() {
+                let board = state.board();
+                pencil
+                    .set_background(Color::Black)
+                    .set_foreground(Color::White);
+                pencil.draw_center_text(
+                    if self.watching {
+                        "enter: back"
+                    } else if state.can_keep_going() {
+                        "enter: results   c: keep going"
+                    } else {
+                        "enter: results"
+                    },
+                    self.tx_to_grid(board.width(), board.height() + 2),
+                );
+            }
+        }
+    }
+
+    fn draw_banner(&self, state: &GameState, pencil: &mut Pencil, text: &str) {
+        let board = state.board();
+        let text = format!(" {text} ");
+        let blank = " ".repeat(text.chars().count());
+        let x = board.width() - text.chars().count() as i32 / 2;
+        let y = board.height() / 3;
+        pencil
+            .set_foreground(Color::Black)
+            .set_background(Color::Xterm(226));
+        pencil.draw_text(&blank, self.tx_to_grid(x, y));
+        pencil.draw_text(&text, self.tx_to_grid(x, y + 1));
+        pencil.draw_text(&blank, self.tx_to_grid(x, y + 2));
+    }
+
+    fn draw_pause_menu(&self, state: &GameState, pencil: &mut Pencil) {
+        let board = state.board();
+        let x = board.width() - PAUSE_MENU_WIDTH / 2;
+        let mut y = board.height() / 3;
+        let blank = " ".repeat(PAUSE_MENU_WIDTH as usize);
+        pencil
+            .set_foreground(Color::White)
+            .set_background(Color::Xterm(236));
+        pencil.draw_text(&blank, self.tx_to_grid(x, y));
+        y += 1;
+        pencil.draw_text(
+            &format!("{:^1$}", "PAUSED", blank.len()),
+            self.tx_to_grid(x, y),
+        );
+        y += 1;
+        pencil.draw_text(&blank, self.tx_to_grid(x, y));
+        y += 1;
+        for (i, entry) in PAUSE_MENU.iter().enumerate() {
+            let text = if i == self.pause_selection {
+                pencil.set_foreground(Color::Xterm(226));
+                format!("  > {entry:<9}")
+            } else {
+                pencil.set_foreground(Color::White);
+                format!("    {entry:<9}")
+            };
+            pencil.draw_text(&text, self.tx_to_grid(x, y));
+            y += 1;
+        }
+        pencil.draw_text(&blank, self.tx_to_grid(x, y));
+    }
+
+    fn draw_gameover(&self, state: &GameState, pencil: &mut Pencil) {
+        pencil
+            .set_foreground(Color::Xterm(230))
+            .set_background(Color::Xterm(100));
+        let text = if state.remaining_time().is_some_and(|time| time.is_zero()) {
+            " TIME UP!  "
+        } else if state.end_reason() == Some(EndReason::Completed) {
+            "  FINISH!  "
+        } else {
+            " GAME OVER "
+        };
+        let Vec2 { x, y } = self.gameover_pos;
+        pencil.draw_text("           ", self.tx_to_grid(x, y));
+        pencil.draw_text(text, self.tx_to_grid(x, y + 1));
+        pencil.draw_text("           ", self.tx_to_grid(x, y + 2));
+    }
+
+    fn draw_running(&self, state: &GameState, pencil: &mut Pencil) {
+        let board = state.board();
+        let width = board.width();
+        let height = board.height();
+
+        // instructions
+        let mut y = 0;
+        pencil.set_foreground(Color::White);
+        pencil.draw_text("left/right: move", self.tx_to_grid(-25, y));
+        y += 1;
+        pencil.draw_text("up/x: rotate", self.tx_to_grid(-25, y));
+        y += 1;
+        pencil.draw_text("z: rotate left", self.tx_to_grid(-25, y));
+        y += 1;
+        pencil.draw_text("a: rotate 180", self.tx_to_grid(-25, y));
+        y += 1;
+        pencil.draw_text("down: soft drop", self.tx_to_grid(-25, y));
+        y += 1;
+        pencil.draw_text("space/return: hard drop", self.tx_to_grid(-25, y));
+        y += 1;
+        if state.rules().hold_enabled {
+            pencil.draw_text("c: hold", self.tx_to_grid(-25, y));
+            y += 1;
+        }
+        y += 1;
+        pencil.draw_text("p/esc: pause", self.tx_to_grid(-25, y));
+        y += 1;
+        pencil.draw_text("q: quit", self.tx_to_grid(-25, y));
+        y += 2;
+        pencil.set_foreground(Color::Xterm(245));
+        pencil.draw_text("seed:", self.tx_to_grid(-25, y));
+        y += 1;
+        pencil.draw_text(&state.seed().to_string(), self.tx_to_grid(-25, y));
+        y += 2;
+
+        // split times, with the time taken since the previous split
+        if let Some(Goal::Lines(_)) = state.rules().goal {
+            pencil.set_foreground(Color::White);
+            pencil.draw_text("splits:", self.tx_to_grid(-25, y));
+            y += 1;
+            let first_shown = self.splits.len().saturating_sub(SPLITS_SHOWN);
+            for (i, split) in self.splits.iter().enumerate().skip(first_shown) {
+                let previous = i.checked_sub(1).map_or(Duration::ZERO, |i| self.splits[i]);
+                let lines = (i as i32 + 1) * SPLIT_LINES;
+                let text = format!(
+                    "{lines:>3} {} +{}",
+                    format_time(*split),
+                    format_time(*split - previous)
+                );
+                pencil.draw_text(&text, self.tx_to_grid(-25, y));
+                y += 1;
+            }
+        }
+
+        // score
+        y = 0;
+        pencil.set_foreground(Color::White);
+        let lines = match state.rules().goal {
+            Some(Goal::Lines(goal)) => format!("lines: {}/{goal}", state.lines_cleared()),
+            Some(Goal::Garbage) => format!("garbage: {}", state.garbage_left()),
+            _ => format!("lines: {}", state.lines_cleared()),
+        };
+        pencil.draw_text(&lines, self.tx_to_grid(width * 2 + 4, y));
+        y += 2;
+
+        // timed games count down, above the board as well so it can't be missed
+        if let Some(remaining) = state.remaining_time() {
+            let color = if remaining < HURRY_TIME {
+                Color::Red
+            } else {
+                Color::Xterm(226)
+            };
+            let remaining = format_time(remaining);
+            pencil.set_foreground(color);
+            pencil.draw_text(
+                &format!("left: {remaining}"),
+                self.tx_to_grid(width * 2 + 4, y),
+            );
+            // above the spawn rows, so pieces coming in don't cover it
+            pencil.draw_center_text(&remaining, self.tx_to_grid(width, -SPAWN_ROWS_SHOWN - 1));
+            pencil.set_foreground(Color::White);
+        } else {
+            pencil.draw_text(
+                &format!("time: {}", format_time(state.elapsed())),
+                self.tx_to_grid(width * 2 + 4, y),
+            );
+        }
+        y += 2;
+
+        pencil.draw_text(
+            &format!("score: {}", state.score()),
+            self.tx_to_grid(width * 2 + 4, y),
+        );
+        y += 2;
+
+        let level = match state.rules().goal {
+            Some(Goal::Level(goal)) => format!("level: {}/{goal}", state.level()),
+            _ => format!("level: {}", state.level()),
+        };
+        pencil.draw_text(&level, self.tx_to_grid(width * 2 + 4, y));
+        y += 2;
+
+        // hold slot, next to the next pieces
+        let hidden = state.is_paused();
+        if state.rules().hold_enabled {
+            pencil.set_foreground(Color::White);
+            pencil.draw_text("hold:", self.tx_to_grid(width * 2 + 20, y));
+            if let Some(tetromino) = state.hold_piece().filter(|_| !hidden) {
+                let mut piece = Piece::new(tetromino);
+                let min_y = piece.cells().first().unwrap().y;
+                piece.pos = Pos::xy(width * 2 + 22, y + 2 - min_y);
+                // greyed out until the current piece locks
+                let color = if state.can_hold() {
+                    tetromino_color(tetromino)
+                } else {
+                    Color::Xterm(240)
+                };
+                self.draw_piece(pencil, &piece, color);
+            }
+        }
+
+        pencil.set_foreground(Color::White);
+        pencil.draw_text("next pieces:", self.tx_to_grid(width * 2 + 4, y));
+        y += 2;
+
+        for tetromino in state.next_pieces().iter().filter(|_| !hidden) {
+            let mut piece = Piece::new(*tetromino);
+            // check the 'exact' size of the pieces
+            let cells = piece.cells();
+            let min_y = cells.first().unwrap().y;
+            let max_y = cells.last().unwrap().y;
+            y -= min_y;
+            piece.pos = Pos::xy(width * 2 + 6, y);
+            y += max_y + 2;
+            self.draw_piece(pencil, &piece, tetromino_color(*tetromino));
+        }
+
+        // draw border
+        pencil
+            .set_background(Color::Black)
+            .set_foreground(Color::Xterm(250));
+        pencil.draw_vline('|', self.tx_to_grid(-1, 0), height);
+        pencil.draw_vline('|', self.tx_to_grid(width * 2, 0), height);
+        pencil.draw_hline('-', self.tx_to_grid(0, height), width * 2);
+        pencil.draw_text("+", self.tx_to_grid(-1, height));
+        pencil.draw_text("+", self.tx_to_grid(width * 2, height));
+
+        // draw grid
+        pencil.set_foreground(Color::Xterm(240));
+        let progress = state.phase_progress();
+        // the spawn rows of the buffer are shown too, but only where something locked
+        for y in -SPAWN_ROWS_SHOWN..height {
+            let clearing = state.clearing_rows().contains(&y);
+            for x in 0..width {
+                let cell = board.cell(Pos::xy(x, y));
+                let pos = self.tx_to_grid(x * 2, y);
+                let color = if hidden {
+                    // no planning ahead while paused
+                    None
+                } else if clearing {
+                    self.clear_animation.color(cell, x, width, progress)
+                } else {
+                    cell_color(cell)
+                };
+                match color {
+                    None if y < 0 => continue,
+                    None => pencil.set_background(Color::Black).draw_text("∙∙", pos),
+                    Some(color) => pencil.set_background(color).draw_text("  ", pos),
+                };
+            }
+        }
+
+        if hidden {
+            return;
+        }
+
+        // draw shadow, then the current piece on top of it.
+        // the buffer stays hidden, except for the rows where pieces spawn
+        let shown = |pos: &Pos| pos.y >= -SPAWN_ROWS_SHOWN;
+        if let Some(ghost) = state.ghost_piece() {
+            pencil.set_background(Color::Xterm(237));
+            for pos in ghost.board_cells().iter().filter(|pos| shown(pos)) {
+                pencil.draw_text("∙∙", self.tx_to_grid(pos.x * 2, pos.y));
+            }
+        }
+        if let Some(piece) = state.current_piece() {
+            pencil.set_background(tetromino_color(piece.tetromino));
+            for pos in piece.board_cells().iter().filter(|pos| shown(pos)) {
+                pencil.draw_text("  ", self.tx_to_grid(pos.x * 2, pos.y));
+            }
+        }
+    }
+
+    //--------------------------------------------------------------------------------
+    // helpers
+    //--------------------------------------------------------------------------------
+
+    fn tx_to_grid(&self, x: i32, y: i32) -> Vec2 {
+        Vec2::xy(x + self.grid_pos.x, y + self.grid_pos.y)
+    }
+
+    // piece.pos is in grid dots here (not cells)
+    fn draw_piece(&self, pencil: &mut Pencil, piece: &Piece, color: Color) {
+        pencil.set_background(color);
+        for cell in piece.cells().iter() {
+            let pos = self.tx_to_grid(piece.pos.x + cell.x * 2, piece.pos.y + cell.y);
+            pencil.draw_text("  ", pos);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::{Key, KeyEvent};
+use ruscii::spatial::Vec2;
+use ruscii::terminal::Color;
+
+use super::mode::Mode;
+use super::screen::{Context, Transition};
+
+// personal bests, as saved in the records file
+pub struct HighScoresScreen;
+
+impl HighScoresScreen {
+    pub fn handle_key_events(&mut self, key_events: &[KeyEvent]) -> Option<Transition> {
+        key_events
+            .iter()
+            .any(|key_event| matches!(key_event, KeyEvent::Pressed(Key::Esc | Key::Enter)))
+            .then_some(Transition::Pop)
+    }
+
+    pub fn draw(&self, ctx: &Context, pencil: &mut Pencil) {
+        let dim = pencil.dimension();
+        let x = dim.x / 2;
+        let mut y = dim.y / 2 - 6;
+
+        pencil
+            .set_background(Color::Black)
+            .set_foreground(Color::Xterm(226));
+        pencil.draw_center_text("HIGH SCORES", Vec2::xy(x, y));
+        y += 2;
+
+        pencil.set_foreground(Color::White);
+        for mode in Mode::all() {
+            let best = match ctx.records.best(&mode.record_key()) {
+                None => "-".to_string(),
+                Some(best) => mode.format_result(best),
+            };
+            pencil.draw_text(&mode.name(), Vec2::xy(x - 12, y));
+            pencil.draw_right_aligned_text(&best, Vec2::xy(x + 12, y));
+            y += 1;
+        }
+        y += 1;
+
+        pencil.set_foreground(Color::Xterm(245));
+        pencil.draw_center_text("esc: back", Vec2::xy(x, y));
+    }
+}
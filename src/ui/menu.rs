@@ -0,0 +1,61 @@
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::Key;
+use ruscii::spatial::Vec2;
+use ruscii::terminal::Color;
+
+// a vertical list of entries, browsed with up/down and picked with enter
+pub struct Menu {
+    entries: Vec<String>,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(entries: Vec<String>) -> Self {
+        Self {
+            entries,
+            selected: 0,
+        }
+    }
+
+    // same number of entries, just relabelled
+    pub fn set_entries(&mut self, entries: Vec<String>) {
+        self.entries = entries;
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    // returns the index of the entry picked, if any
+    pub fn handle_key(&mut self, key: Key) -> Option<usize> {
+        let len = self.entries.len();
+        if len == 0 {
+            return None;
+        }
+        match key {
+            Key::Up => self.selected = (self.selected + len - 1) % len,
+            Key::Down => self.selected = (self.selected + 1) % len,
+            Key::Enter | Key::Space => return Some(self.selected),
+            _ => (),
+        }
+        None
+    }
+
+    // entries are left aligned, in a column centered on `center`
+    pub fn draw(&self, pencil: &mut Pencil, center: Vec2) {
+        let width = self.entries.iter().map(|entry| entry.chars().count()).max();
+        let x = center.x - (width.unwrap_or_default() as i32 + 2) / 2;
+        pencil.set_background(Color::Black);
+        for (i, entry) in self.entries.iter().enumerate() {
+            let pos = Vec2::xy(x, center.y + i as i32);
+            if i == self.selected {
+                pencil.set_foreground(Color::Xterm(226));
+                pencil.draw_text(&format!("> {entry}"), pos);
+            } else {
+                pencil.set_foreground(Color::White);
+                pencil.draw_text(&format!("  {entry}"), pos);
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use ruscii::terminal::Color;
+use tetris::{Cell, Tetromino};
+
+pub mod game;
+pub mod gameview;
+pub mod highscores;
+pub mod menu;
+pub mod mode;
+pub mod modeselect;
+pub mod replays;
+pub mod results;
+pub mod screen;
+pub mod settings;
+pub mod title;
+
+pub fn tetromino_color(tetromino: Tetromino) -> Color {
+    match tetromino {
+        Tetromino::O => Color::Yellow,
+        Tetromino::I => Color::Cyan,
+        Tetromino::S => Color::Green,
+        Tetromino::Z => Color::Red,
+        Tetromino::L => Color::Xterm(208),
+        Tetromino::J => Color::Blue,
+        Tetromino::T => Color::Magenta,
+    }
+}
+
+// None for empty cells
+pub fn cell_color(cell: Cell) -> Option<Color> {
+    match cell {
+        Cell::Empty => None,
+        Cell::Tetromino(tetromino) => Some(tetromino_color(tetromino)),
+        Cell::Garbage => Some(Color::Xterm(244)),
+    }
+}
+
+// m:ss.mmm
+pub fn format_time(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+// the next (or previous) item of a list, wrapping around
+pub fn cycle<T: Copy + PartialEq>(all: &[T], current: T, delta: i32) -> T {
+    let index = all.iter().position(|item| *item == current).unwrap_or(0) as i32;
+    all[(index + delta).rem_euclid(all.len() as i32) as usize]
+}
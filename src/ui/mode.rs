@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use tetris::{EndReason, GameState, Goal, Gravity, LevelGoal, Rules};
+
+use super::{cycle, format_time};
+
+pub const SPRINT_LINES: [i32; 3] = [20, 40, 100];
+pub const ULTRA_MINUTES: [u64; 3] = [1, 2, 5];
+// None keeps going after the last level
+pub const MARATHON_LINES: [Option<i32>; 3] = [Some(150), Some(200), None];
+pub const CHEESE_ROWS: [i32; 3] = [10, 18, 100];
+
+// a way to play: what ends the game and what counts as a result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Endless,
+    // clear this many lines as fast as possible
+    Sprint(i32),
+    // score as much as possible in this many minutes
+    Ultra(u64),
+    // level up every 10 lines (or variable goal), the game speeds up along the way
+    Marathon(Option<i32>),
+    // dig through this many rows of garbage as fast as possible
+    Cheese(i32),
+}
+
+impl Mode {
+    // every mode and variant, in the order they are listed
+    pub fn all() -> Vec<Mode> {
+        let mut modes = vec![Mode::Endless];
+        modes.extend(SPRINT_LINES.map(Mode::Sprint));
+        modes.extend(ULTRA_MINUTES.map(Mode::Ultra));
+        modes.extend(MARATHON_LINES.map(Mode::Marathon));
+        modes.extend(CHEESE_ROWS.map(Mode::Cheese));
+        modes
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Mode::Endless => "endless".to_string(),
+            Mode::Sprint(lines) => format!("sprint {lines}"),
+            Mode::Ultra(minutes) => format!("ultra {minutes}min"),
+            Mode::Marathon(Some(lines)) => format!("marathon {lines}"),
+            Mode::Marathon(None) => "marathon endless".to_string(),
+            Mode::Cheese(rows) => format!("cheese {rows}"),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Mode::Endless => "play until you top out".to_string(),
+            Mode::Sprint(lines) => format!("clear {lines} lines as fast as you can"),
+            Mode::Ultra(minutes) => format!("score as much as you can in {minutes} minutes"),
+            Mode::Marathon(Some(lines)) => {
+                format!("{} levels, faster with every one", lines / 10)
+            }
+            Mode::Marathon(None) => "faster with every level, up to 20G".to_string(),
+            Mode::Cheese(rows) => format!("dig through {rows} rows of garbage as fast as you can"),
+        }
+    }
+
+    // the other variant of the same mode, when there is one
+    pub fn variant(&self, delta: i32) -> Mode {
+        match self {
+            Mode::Endless => Mode::Endless,
+            Mode::Sprint(lines) => Mode::Sprint(cycle(&SPRINT_LINES, *lines, delta)),
+            Mode::Ultra(minutes) => Mode::Ultra(cycle(&ULTRA_MINUTES, *minutes, delta)),
+            Mode::Marathon(lines) => Mode::Marathon(cycle(&MARATHON_LINES, *lines, delta)),
+            Mode::Cheese(rows) => Mode::Cheese(cycle(&CHEESE_ROWS, *rows, delta)),
+        }
+    }
+
+    pub fn apply(&self, rules: &mut Rules) {
+        rules.goal = match self {
+            Mode::Endless => None,
+            Mode::Sprint(lines) => Some(Goal::Lines(*lines)),
+            Mode::Ultra(minutes) => Some(Goal::Time(Duration::from_secs(minutes * 60))),
+            // 10 lines a level, unless the variable goal was picked in the settings
+            Mode::Marathon(lines) => lines.map(|lines| Goal::Level(lines / 10)),
+            Mode::Cheese(_) => Some(Goal::Garbage),
+        };
+        rules.garbage_rows = match self {
+            Mode::Cheese(rows) => *rows,
+            _ => 0,
+        };
+
+        if matches!(self, Mode::Marathon(_)) {
+            // a fixed speed from the command line wins
+            if !matches!(rules.gravity, Gravity::Fixed(_)) {
+                rules.gravity = Gravity::Guideline;
+            }
+            // levels go by lines here, the variable goal is left alone if picked in the settings
+            if let LevelGoal::Points(_) = rules.level_goal {
+                rules.level_goal = LevelGoal::Lines(10);
+            }
+            // the goal caps the level until the player keeps going past it
+            rules.max_level = 20;
+        }
+    }
+
+    // key of the personal best in the records file
+    pub fn record_key(&self) -> String {
+        self.name().replace(' ', "-")
+    }
+
+    pub fn lower_is_better(&self) -> bool {
+        match self {
+            Mode::Endless | Mode::Ultra(_) | Mode::Marathon(_) => false,
+            Mode::Sprint(_) | Mode::Cheese(_) => true,
+        }
+    }
+
+    // what gets ranked, None when the game doesn't count (topped out before the goal)
+    pub fn result(&self, state: &GameState) -> Option<u64> {
+        let completed = state.end_reason() == Some(EndReason::Completed);
+        match self {
+            Mode::Endless => Some(state.score().max(0) as u64),
+            Mode::Sprint(_) | Mode::Cheese(_) => {
+                completed.then(|| state.elapsed().as_millis() as u64)
+            }
+            Mode::Ultra(_) => completed.then(|| state.score().max(0) as u64),
+            // ranked on the score at the goal, whatever was scored after keeping going
+            Mode::Marathon(Some(_)) => state.completed_score().map(|score| score.max(0) as u64),
+            Mode::Marathon(None) => Some(state.score().max(0) as u64),
+        }
+    }
+
+    pub fn format_result(&self, value: u64) -> String {
+        match self {
+            Mode::Endless | Mode::Ultra(_) | Mode::Marathon(_) => value.to_string(),
+            Mode::Sprint(_) | Mode::Cheese(_) => format_time(Duration::from_millis(value)),
+        }
+    }
+}
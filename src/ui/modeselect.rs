@@ -0,0 +1,98 @@
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::{Key, KeyEvent};
+use ruscii::spatial::Vec2;
+use ruscii::terminal::Color;
+
+use super::game::GameScreen;
+use super::menu::Menu;
+use super::mode::Mode;
+use super::screen::{Context, Screen, Transition};
+
+// one entry per mode, left/right picks the variant
+pub struct ModeSelectScreen {
+    modes: Vec<Mode>,
+    menu: Menu,
+}
+
+fn entries(modes: &[Mode]) -> Vec<String> {
+    modes
+        .iter()
+        .map(|mode| {
+            if mode.variant(1) == *mode {
+                mode.name()
+            } else {
+                format!("< {} >", mode.name())
+            }
+        })
+        .collect()
+}
+
+impl ModeSelectScreen {
+    pub fn new() -> Self {
+        let modes = vec![
+            Mode::Endless,
+            Mode::Sprint(40),
+            Mode::Ultra(2),
+            Mode::Marathon(Some(150)),
+            Mode::Cheese(18),
+        ];
+        Self {
+            menu: Menu::new(entries(&modes)),
+            modes,
+        }
+    }
+
+    pub fn handle_key_events(
+        &mut self,
+        ctx: &Context,
+        key_events: &[KeyEvent],
+    ) -> Option<Transition> {
+        for key_event in key_events {
+            let KeyEvent::Pressed(key) = key_event else {
+                continue;
+            };
+            let selected = self.menu.selected();
+            match key {
+                Key::Esc => return Some(Transition::Pop),
+                Key::Left | Key::Right => {
+                    let delta = if *key == Key::Left { -1 } else { 1 };
+                    self.modes[selected] = self.modes[selected].variant(delta);
+                    self.menu.set_entries(entries(&self.modes));
+                }
+                _ => {
+                    if let Some(i) = self.menu.handle_key(*key) {
+                        let game = GameScreen::new(ctx, self.modes[i]);
+                        return Some(Transition::Push(Screen::Game(Box::new(game))));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    pub fn draw(&self, pencil: &mut Pencil) {
+        let dim = pencil.dimension();
+        let x = dim.x / 2;
+        let mut y = dim.y / 2 - self.modes.len() as i32 / 2 - 3;
+
+        pencil
+            .set_background(Color::Black)
+            .set_foreground(Color::Xterm(226));
+        pencil.draw_center_text("MODE", Vec2::xy(x, y));
+        y += 2;
+
+        self.menu.draw(pencil, Vec2::xy(x, y));
+        y += self.modes.len() as i32 + 1;
+
+        let description = self.modes[self.menu.selected()].description();
+        pencil.set_foreground(Color::White);
+        pencil.draw_center_text(&description, Vec2::xy(x, y));
+        y += 2;
+
+        pencil.set_foreground(Color::Xterm(245));
+        pencil.draw_center_text(
+            "left/right: variant   enter: play   esc: back",
+            Vec2::xy(x, y),
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::{Key, KeyEvent};
+use ruscii::spatial::Vec2;
+use ruscii::terminal::Color;
+use tetris::{GameState, Replay};
+
+use super::format_time;
+use super::game::GameScreen;
+use super::menu::Menu;
+use super::mode::Mode;
+use super::screen::{Context, Screen, Transition};
+
+// only kept for the session, the oldest ones go first
+pub const MAX_REPLAYS: usize = 10;
+
+pub struct SavedReplay {
+    pub mode: Mode,
+    pub replay: Replay,
+    pub summary: String,
+}
+
+impl SavedReplay {
+    pub fn new(state: &GameState, mode: Mode) -> Self {
+        Self {
+            mode,
+            replay: state.replay(),
+            summary: format!(
+                "{:<11} score {:>7}  lines {:>4}  {:>9}  seed {}",
+                mode.name(),
+                state.score(),
+                state.lines_cleared(),
+                format_time(state.elapsed()),
+                state.seed()
+            ),
+        }
+    }
+}
+
+// the games finished during this session, most recent first
+pub struct ReplaysScreen {
+    menu: Menu,
+}
+
+impl ReplaysScreen {
+    pub fn new(ctx: &Context) -> Self {
+        let entries = ctx
+            .replays
+            .iter()
+            .rev()
+            .map(|saved| saved.summary.clone())
+            .collect();
+        Self {
+            menu: Menu::new(entries),
+        }
+    }
+
+    pub fn handle_key_events(
+        &mut self,
+        ctx: &mut Context,
+        key_events: &[KeyEvent],
+    ) -> Option<Transition> {
+        for key_event in key_events {
+            let KeyEvent::Pressed(key) = key_event else {
+                continue;
+            };
+            if *key == Key::Esc {
+                return Some(Transition::Pop);
+            }
+            if let Some(i) = self.menu.handle_key(*key) {
+                let saved = &ctx.replays[ctx.replays.len() - 1 - i];
+                let game = GameScreen::replay(ctx, saved.mode, &saved.replay);
+                return Some(Transition::Push(Screen::Game(Box::new(game))));
+            }
+        }
+        None
+    }
+
+    pub fn draw(&self, ctx: &Context, pencil: &mut Pencil) {
+        let dim = pencil.dimension();
+        let x = dim.x / 2;
+        let mut y = dim.y / 2 - MAX_REPLAYS as i32 / 2 - 3;
+
+        pencil
+            .set_background(Color::Black)
+            .set_foreground(Color::Xterm(226));
+        pencil.draw_center_text("REPLAYS", Vec2::xy(x, y));
+        y += 2;
+
+        if ctx.replays.is_empty() {
+            pencil.set_foreground(Color::White);
+            pencil.draw_center_text("finish a game to watch it here", Vec2::xy(x, y));
+        } else {
+            self.menu.draw(pencil, Vec2::xy(x, y));
+        }
+        y += MAX_REPLAYS as i32 + 1;
+
+        pencil.set_foreground(Color::Xterm(245));
+        pencil.draw_center_text("enter: watch   esc: back", Vec2::xy(x, y));
+    }
+}
@@ -0,0 +1,135 @@
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::{Key, KeyEvent};
+use ruscii::spatial::Vec2;
+use ruscii::terminal::Color;
+use tetris::GameState;
+
+use super::format_time;
+use super::gameview::Action;
+use super::mode::Mode;
+use crate::records::Records;
+
+// summary of a finished game, compared to the personal best for its mode
+pub struct ResultsView {
+    mode: Mode,
+    // played with the standard rules
+    ranked: bool,
+    // what the mode ranks, None if the game didn't count
+    result: Option<u64>,
+    score: i32,
+    lines: i32,
+    time: String,
+    pieces: u32,
+    pps: f64,
+    previous_best: Option<u64>,
+    new_best: bool,
+}
+
+impl ResultsView {
+    // also records the game if ranked, so build it only once per game
+    pub fn new(state: &GameState, mode: Mode, ranked: bool, records: &mut Records) -> Self {
+        let elapsed = state.elapsed();
+        let pieces = state.stats().pieces;
+        let key = mode.record_key();
+        let previous_best = records.best(&key);
+        let result = mode.result(state).filter(|_| ranked);
+        let new_best =
+            result.is_some_and(|result| records.submit(&key, result, mode.lower_is_better()));
+        Self {
+            mode,
+            ranked,
+            result,
+            score: state.score(),
+            lines: state.lines_cleared(),
+            time: format_time(elapsed),
+            pieces,
+            pps: if elapsed.is_zero() {
+                0.0
+            } else {
+                pieces as f64 / elapsed.as_secs_f64()
+            },
+            previous_best,
+            new_best,
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn handle_key_events(&mut self, key_events: &[KeyEvent]) -> Option<Action> {
+        key_events.iter().find_map(|key_event| match key_event {
+            KeyEvent::Pressed(Key::Enter | Key::R | Key::Space) => Some(Action::Restart),
+            KeyEvent::Pressed(Key::Esc) => Some(Action::Quit),
+            _ => None,
+        })
+    }
+
+    // how this game compares to the previous best
+    fn comparison(&self) -> Option<String> {
+        if !self.ranked {
+            return Some("custom settings, not ranked".to_string());
+        }
+        let Some(result) = self.result else {
+            return Some("not finished, not ranked".to_string());
+        };
+        if self.previous_best.is_none() {
+            return Some("first ranked game for this mode".to_string());
+        }
+        if self.new_best {
+            return Some("new personal best!".to_string());
+        }
+        let best = self.previous_best?;
+        let text = match (result.abs_diff(best), self.mode.lower_is_better()) {
+            (0, _) => "matched your best".to_string(),
+            (behind, true) => format!("{} behind your best", self.mode.format_result(behind)),
+            (behind, false) => format!("{behind} short of your best"),
+        };
+        Some(text)
+    }
+
+    pub fn draw(&self, pencil: &mut Pencil) {
+        let dim = pencil.dimension();
+        let x = dim.x / 2;
+        let mut y = dim.y / 2 - 8;
+
+        pencil
+            .set_background(Color::Black)
+            .set_foreground(Color::Xterm(226));
+        pencil.draw_center_text("RESULTS", Vec2::xy(x, y));
+        y += 1;
+        pencil.set_foreground(Color::Xterm(245));
+        pencil.draw_center_text(&self.mode.name(), Vec2::xy(x, y));
+        y += 2;
+
+        let best = match self.previous_best {
+            None => "-".to_string(),
+            Some(best) => self.mode.format_result(best),
+        };
+        let rows = [
+            ("score", self.score.to_string()),
+            ("lines", self.lines.to_string()),
+            ("time", self.time.clone()),
+            ("pieces", self.pieces.to_string()),
+            ("pps", format!("{:.2}", self.pps)),
+            ("best", best),
+        ];
+        pencil.set_foreground(Color::White);
+        for (name, value) in rows {
+            pencil.draw_text(&format!("{name:<8}"), Vec2::xy(x - 10, y));
+            pencil.draw_right_aligned_text(&value, Vec2::xy(x + 10, y));
+            y += 1;
+        }
+        y += 1;
+
+        if let Some(text) = self.comparison() {
+            let color = if self.new_best { 226 } else { 245 };
+            pencil.set_foreground(Color::Xterm(color));
+            pencil.draw_center_text(&text, Vec2::xy(x, y));
+        }
+        y += 2;
+
+        pencil.set_foreground(Color::Xterm(245));
+        pencil.draw_center_text("enter: play again   esc: menu", Vec2::xy(x, y));
+    }
+}
@@ -0,0 +1,91 @@
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::KeyEvent;
+use ruscii::spatial::Vec2;
+
+use super::game::GameScreen;
+use super::gameview::Action;
+use super::highscores::HighScoresScreen;
+use super::modeselect::ModeSelectScreen;
+use super::replays::{ReplaysScreen, SavedReplay, MAX_REPLAYS};
+use super::results::ResultsView;
+use super::settings::{Settings, SettingsScreen};
+use super::title::TitleScreen;
+use crate::records::Records;
+
+// what all the screens share
+pub struct Context {
+    pub win_size: Vec2,
+    pub settings: Settings,
+    pub records: Records,
+    // finished games of this session, the most recent last
+    pub replays: Vec<SavedReplay>,
+}
+
+impl Context {
+    pub fn save_replay(&mut self, replay: SavedReplay) {
+        if self.replays.len() == MAX_REPLAYS {
+            self.replays.remove(0);
+        }
+        self.replays.push(replay);
+    }
+}
+
+// how the screen stack changes after a screen handled its keys
+pub enum Transition {
+    Push(Screen),
+    Replace(Screen),
+    Pop,
+    Quit,
+}
+
+pub enum Screen {
+    Title(TitleScreen),
+    ModeSelect(ModeSelectScreen),
+    Settings(SettingsScreen),
+    HighScores(HighScoresScreen),
+    Replays(ReplaysScreen),
+    Game(Box<GameScreen>),
+    Results(ResultsView),
+}
+
+impl Screen {
+    pub fn handle_key_events(
+        &mut self,
+        ctx: &mut Context,
+        key_events: &[KeyEvent],
+    ) -> Option<Transition> {
+        match self {
+            Screen::Title(title) => title.handle_key_events(ctx, key_events),
+            Screen::ModeSelect(modes) => modes.handle_key_events(ctx, key_events),
+            Screen::Settings(settings) => settings.handle_key_events(&mut ctx.settings, key_events),
+            Screen::HighScores(highscores) => highscores.handle_key_events(key_events),
+            Screen::Replays(replays) => replays.handle_key_events(ctx, key_events),
+            Screen::Game(game) => game.handle_key_events(ctx, key_events),
+            Screen::Results(results) => match results.handle_key_events(key_events)? {
+                Action::Restart => Some(Transition::Replace(Screen::Game(Box::new(
+                    GameScreen::new(ctx, results.mode()),
+                )))),
+                _ => Some(Transition::Pop),
+            },
+        }
+    }
+
+    // only games move on their own
+    pub fn update(&mut self, step: usize) {
+        if let Screen::Game(game) = self {
+            game.update(step);
+        }
+    }
+
+    pub fn draw(&self, ctx: &Context, pencil: &mut Pencil) {
+        match self {
+            Screen::Title(title) => title.draw(pencil),
+            Screen::ModeSelect(modes) => modes.draw(pencil),
+            Screen::Settings(settings) => settings.draw(&ctx.settings, pencil),
+            Screen::HighScores(highscores) => highscores.draw(ctx, pencil),
+            Screen::Replays(replays) => replays.draw(ctx, pencil),
+            Screen::Game(game) => game.draw(pencil),
+            Screen::Results(results) => results.draw(pencil),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::time::Duration;
+
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::{Key, KeyEvent};
+use ruscii::spatial::Vec2;
+use ruscii::terminal::Color;
+use tetris::board::MIN_SIZE;
+use tetris::{Handling, LevelGoal, LockReset, RandomizerKind, Rules, ScoringTable};
+
+use super::cycle;
+use super::gameview::ClearAnimation;
+use super::screen::Transition;
+
+// what the player can tweak, from the command line or the settings screen
+#[derive(Debug, Clone)]
+pub struct Settings {
+    // None picks a new one for each game
+    pub seed: Option<u64>,
+    pub rules: Rules,
+    pub handling: Handling,
+    pub clear_animation: ClearAnimation,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            // leave some time for the line clear animation
+            rules: Rules {
+                line_clear_delay: Duration::from_millis(250),
+                ..Rules::default()
+            },
+            handling: Handling::default(),
+            clear_animation: ClearAnimation::default(),
+        }
+    }
+}
+
+impl Settings {
+    // games only make the records with these rules and a random seed,
+    // handling and animations are up to the player
+    pub fn is_ranked(&self) -> bool {
+        self.seed.is_none() && self.rules == Settings::default().rules
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Randomizer,
+    Hold,
+    AllSpin,
+    Scoring,
+    Width,
+    Height,
+    LevelGoal,
+    LockDelay,
+    LockReset,
+    GarbageMessiness,
+    Das,
+    Arr,
+    SoftDropFactor,
+    EntryDelay,
+    LineClearDelay,
+    ClearAnimation,
+}
+
+const LEVEL_GOALS: [LevelGoal; 3] = [
+    LevelGoal::Lines(10),
+    LevelGoal::Points(1000),
+    LevelGoal::Variable,
+];
+
+const FIELDS: [Field; 16] = [
+    Field::Randomizer,
+    Field::Hold,
+    Field::AllSpin,
+    Field::Scoring,
+    Field::Width,
+    Field::Height,
+    Field::LevelGoal,
+    Field::LockDelay,
+    Field::LockReset,
+    Field::GarbageMessiness,
+    Field::Das,
+    Field::Arr,
+    Field::SoftDropFactor,
+    Field::EntryDelay,
+    Field::LineClearDelay,
+    Field::ClearAnimation,
+];
+
+// going past the move limits gives step reset on one side and infinity on the other
+const MAX_LOCK_RESETS: u32 = 30;
+
+fn adjust_lock_reset(lock_reset: LockReset, delta: i32) -> LockReset {
+    let resets = match lock_reset {
+        LockReset::Step => -1,
+        LockReset::Move(resets) => resets.min(MAX_LOCK_RESETS) as i32,
+        LockReset::Infinity => MAX_LOCK_RESETS as i32 + 1,
+    };
+    match resets + delta {
+        resets if resets < 0 => LockReset::Step,
+        resets if resets > MAX_LOCK_RESETS as i32 => LockReset::Infinity,
+        resets => LockReset::Move(resets as u32),
+    }
+}
+
+fn adjust_millis(duration: &mut Duration, delta: i32, step: i32) {
+    let millis = (duration.as_millis() as i32 + delta * step).max(0);
+    *duration = Duration::from_millis(millis as u64);
+}
+
+impl Field {
+    fn name(&self) -> &'static str {
+        match self {
+            Field::Randomizer => "randomizer",
+            Field::Hold => "hold",
+            Field::AllSpin => "all spin",
+            Field::Scoring => "scoring",
+            Field::Width => "board width",
+            Field::Height => "board height",
+            Field::LevelGoal => "level up every",
+            Field::LockDelay => "lock delay",
+            Field::LockReset => "lock reset",
+            Field::GarbageMessiness => "garbage messiness",
+            Field::Das => "das",
+            Field::Arr => "arr",
+            Field::SoftDropFactor => "soft drop factor",
+            Field::EntryDelay => "entry delay",
+            Field::LineClearDelay => "line clear delay",
+            Field::ClearAnimation => "clear animation",
+        }
+    }
+
+    fn value(&self, settings: &Settings) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        let millis = |duration: Duration| format!("{}ms", duration.as_millis());
+        match self {
+            Field::Randomizer => settings.rules.randomizer.name().to_string(),
+            Field::Hold => on_off(settings.rules.hold_enabled),
+            Field::AllSpin => on_off(settings.rules.all_spin),
+            Field::Scoring => settings.rules.scoring.name().to_string(),
+            Field::Width => settings.rules.board_width.to_string(),
+            Field::Height => settings.rules.board_height.to_string(),
+            Field::LevelGoal => match settings.rules.level_goal {
+                LevelGoal::Points(points) => format!("{points} points"),
+                LevelGoal::Lines(lines) => format!("{lines} lines"),
+                LevelGoal::Variable => "5 x level lines".to_string(),
+            },
+            Field::LockDelay => millis(settings.rules.lock_delay),
+            Field::LockReset => match settings.rules.lock_reset {
+                LockReset::Move(resets) => format!("{resets} moves"),
+                LockReset::Infinity => "infinity".to_string(),
+                LockReset::Step => "step".to_string(),
+            },
+            Field::GarbageMessiness => {
+                format!("{:.0}%", settings.rules.garbage_messiness * 100.0)
+            }
+            Field::Das => millis(settings.handling.das),
+            Field::Arr => millis(settings.handling.arr),
+            Field::SoftDropFactor => settings.handling.soft_drop_factor.to_string(),
+            Field::EntryDelay => millis(settings.rules.entry_delay),
+            Field::LineClearDelay => millis(settings.rules.line_clear_delay),
+            Field::ClearAnimation => settings.clear_animation.name().to_string(),
+        }
+    }
+
+    fn adjust(&self, settings: &mut Settings, delta: i32) {
+        let rules = &mut settings.rules;
+        let handling = &mut settings.handling;
+        match self {
+            Field::Randomizer => {
+                rules.randomizer = cycle(&RandomizerKind::ALL, rules.randomizer, delta)
+            }
+            Field::Hold => rules.hold_enabled = !rules.hold_enabled,
+            Field::AllSpin => rules.all_spin = !rules.all_spin,
+            Field::Scoring => {
+                let name = cycle(&ScoringTable::NAMES, rules.scoring.name(), delta);
+                rules.scoring = name.parse().unwrap_or_default();
+            }
+            Field::Width => rules.board_width = (rules.board_width + delta).max(MIN_SIZE),
+            Field::Height => rules.board_height = (rules.board_height + delta).max(MIN_SIZE),
+            Field::LevelGoal => rules.level_goal = cycle(&LEVEL_GOALS, rules.level_goal, delta),
+            Field::LockDelay => adjust_millis(&mut rules.lock_delay, delta, 50),
+            Field::LockReset => rules.lock_reset = adjust_lock_reset(rules.lock_reset, delta),
+            Field::GarbageMessiness => {
+                let messiness = rules.garbage_messiness + delta as f64 * 0.1;
+                rules.garbage_messiness = (messiness * 10.0).round().clamp(0.0, 10.0) / 10.0;
+            }
+            Field::Das => adjust_millis(&mut handling.das, delta, 10),
+            Field::Arr => adjust_millis(&mut handling.arr, delta, 10),
+            Field::SoftDropFactor => {
+                handling.soft_drop_factor = (handling.soft_drop_factor + delta * 5).max(1)
+            }
+            Field::EntryDelay => adjust_millis(&mut rules.entry_delay, delta, 50),
+            Field::LineClearDelay => adjust_millis(&mut rules.line_clear_delay, delta, 50),
+            Field::ClearAnimation => {
+                settings.clear_animation =
+                    cycle(&ClearAnimation::ALL, settings.clear_animation, delta)
+            }
+        }
+    }
+}
+
+// edits the settings in place, they apply to the next game
+pub struct SettingsScreen {
+    selected: usize,
+}
+
+impl SettingsScreen {
+    pub fn new() -> Self {
+        Self { selected: 0 }
+    }
+
+    pub fn handle_key_events(
+        &mut self,
+        settings: &mut Settings,
+        key_events: &[KeyEvent],
+    ) -> Option<Transition> {
+        for key_event in key_events {
+            let KeyEvent::Pressed(key) = key_event else {
+                continue;
+            };
+            let field = FIELDS[self.selected];
+            match key {
+                Key::Up => self.selected = (self.selected + FIELDS.len() - 1) % FIELDS.len(),
+                Key::Down => self.selected = (self.selected + 1) % FIELDS.len(),
+                Key::Left => field.adjust(settings, -1),
+                Key::Right => field.adjust(settings, 1),
+                Key::Esc | Key::Enter => return Some(Transition::Pop),
+                _ => (),
+            }
+        }
+        None
+    }
+
+    pub fn draw(&self, settings: &Settings, pencil: &mut Pencil) {
+        let dim = pencil.dimension();
+        let x = dim.x / 2;
+        let mut y = dim.y / 2 - FIELDS.len() as i32 / 2 - 3;
+
+        pencil
+            .set_background(Color::Black)
+            .set_foreground(Color::Xterm(226));
+        pencil.draw_center_text("SETTINGS", Vec2::xy(x, y));
+        y += 2;
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let color = if i == self.selected {
+                Color::Xterm(226)
+            } else {
+                Color::White
+            };
+            pencil.set_foreground(color);
+            pencil.draw_text(field.name(), Vec2::xy(x - 16, y));
+            pencil.draw_right_aligned_text(
+                &format!("< {} >", field.value(settings)),
+                Vec2::xy(x + 16, y),
+            );
+            y += 1;
+        }
+        y += 1;
+
+        pencil.set_foreground(Color::Xterm(245));
+        pencil.draw_center_text(
+            "up/down: select   left/right: change   esc: back",
+            Vec2::xy(x, y),
+        );
+    }
+}
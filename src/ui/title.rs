@@ -0,0 +1,87 @@
+use ruscii::drawing::Pencil;
+use ruscii::keyboard::{Key, KeyEvent};
+use ruscii::spatial::Vec2;
+use ruscii::terminal::Color;
+use tetris::Tetromino;
+
+use super::highscores::HighScoresScreen;
+use super::menu::Menu;
+use super::modeselect::ModeSelectScreen;
+use super::replays::ReplaysScreen;
+use super::screen::{Context, Screen, Transition};
+use super::settings::SettingsScreen;
+use super::tetromino_color;
+
+const TITLE: [&str; 5] = [
+    "##### ##### ##### ####  ##### #####",
+    "  #   #       #   #   #   #   #    ",
+    "  #   ####    #   ####    #   #####",
+    "  #   #       #   #  #    #       #",
+    "  #   #####   #   #   # ##### #####",
+];
+
+const ENTRIES: [&str; 5] = ["play", "settings", "high scores", "replays", "quit"];
+
+// bottom of the stack, leaving it quits
+pub struct TitleScreen {
+    menu: Menu,
+}
+
+impl TitleScreen {
+    pub fn new() -> Self {
+        Self {
+            menu: Menu::new(ENTRIES.iter().map(|entry| entry.to_string()).collect()),
+        }
+    }
+
+    pub fn handle_key_events(
+        &mut self,
+        ctx: &Context,
+        key_events: &[KeyEvent],
+    ) -> Option<Transition> {
+        for key_event in key_events {
+            let KeyEvent::Pressed(key) = key_event else {
+                continue;
+            };
+            if *key == Key::Esc {
+                return Some(Transition::Quit);
+            }
+            let screen = match self.menu.handle_key(*key) {
+                Some(0) => Screen::ModeSelect(ModeSelectScreen::new()),
+                Some(1) => Screen::Settings(SettingsScreen::new()),
+                Some(2) => Screen::HighScores(HighScoresScreen),
+                Some(3) => Screen::Replays(ReplaysScreen::new(ctx)),
+                Some(_) => return Some(Transition::Quit),
+                None => continue,
+            };
+            return Some(Transition::Push(screen));
+        }
+        None
+    }
+
+    pub fn draw(&self, pencil: &mut Pencil) {
+        let dim = pencil.dimension();
+        let x = dim.x / 2;
+        let mut y = dim.y / 2 - 8;
+
+        // one letter per piece color
+        pencil.set_background(Color::Black);
+        let width = TITLE[0].len() as i32;
+        for line in TITLE {
+            for (i, letter) in line.as_bytes().chunks(6).enumerate() {
+                let color = tetromino_color(Tetromino::ALL[i % Tetromino::ALL.len()]);
+                let text = String::from_utf8_lossy(letter);
+                pencil.set_foreground(color);
+                pencil.draw_text(&text, Vec2::xy(x - width / 2 + i as i32 * 6, y));
+            }
+            y += 1;
+        }
+        y += 2;
+
+        self.menu.draw(pencil, Vec2::xy(x, y));
+        y += ENTRIES.len() as i32 + 1;
+
+        pencil.set_foreground(Color::Xterm(245));
+        pencil.draw_center_text("up/down: select   enter: ok   q: quit", Vec2::xy(x, y));
+    }
+}